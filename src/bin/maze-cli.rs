@@ -0,0 +1,268 @@
+//! Stage authoring from the shell, on the stage files the game loads.
+//!
+//! Maps are printed like the game draws them, the first line of the file at the bottom,
+//! so that the moves of `solve` point the same way as the arrow keys.
+use std::{collections::HashSet, process::ExitCode};
+
+use maze_game::{
+    game::Game,
+    generator::{generate, Algorithm, GeneratorConfig},
+    grid::{Direction, Position},
+    stage::{solve, Stage, Tile},
+};
+
+const USAGE: &str = "\
+usage:
+    maze-cli validate FILE...   parse the stage files and report what would make them unplayable
+    maze-cli solve FILE         print one of the shortest solutions over the map
+    maze-cli stats FILE...      print the size, dead ends and solution length of the stages
+    maze-cli generate [--algorithm backtracker|prim|kruskal|wilson|eller]
+                      [--width CELLS] [--height CELLS] [--braid PROBABILITY] [--seed SEED]
+                                print a generated stage, 2 * CELLS - 1 tiles wide and high";
+
+/// something to fix in a stage which still parses
+#[derive(Debug, PartialEq)]
+enum Lint {
+    /// no key opens the door, an error
+    DoorWithoutKey { letter: char, at: (i32, i32) },
+    /// the key opens no door, a warning
+    KeyWithoutDoor { letter: char, at: (i32, i32) },
+    /// no sequence of moves clears the stage, an error
+    Unsolvable { reachable: usize },
+}
+
+impl Lint {
+    fn is_error(&self) -> bool {
+        !matches!(self, Lint::KeyWithoutDoor { .. })
+    }
+}
+
+impl std::fmt::Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Lint::DoorWithoutKey { letter, at: (y, x) } => write!(
+                f, "row {}, column {}: no key '{}' opens door '{}'",
+                y + 1, x + 1, letter, letter.to_ascii_uppercase()
+            ),
+            Lint::KeyWithoutDoor { letter, at: (y, x) } => write!(
+                f, "row {}, column {}: key '{}' opens no door", y + 1, x + 1, letter
+            ),
+            Lint::Unsolvable { reachable } => write!(
+                f, "unsolvable, no goal among the {} cells the player can reach", reachable
+            ),
+        }
+    }
+}
+
+fn lint(stage: &Stage) -> Vec<Lint> {
+    let letters = |wanted: fn(Tile) -> Option<char>| {
+        stage.cells().filter_map(|(_, tile)| wanted(tile)).collect::<HashSet<_>>()
+    };
+    let keys = letters(|tile| match tile {
+        Tile::Key(letter) => Some(letter),
+        _ => None,
+    });
+    let doors = letters(|tile| match tile {
+        Tile::Door(letter) => Some(letter),
+        _ => None,
+    });
+
+    let mut lints = stage.cells()
+        .filter_map(|(at, tile)| match tile {
+            Tile::Door(letter) if !keys.contains(&letter) => Some(Lint::DoorWithoutKey { letter, at }),
+            Tile::Key(letter) if !doors.contains(&letter) => Some(Lint::KeyWithoutDoor { letter, at }),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if let Err(unsolvable) = solve(stage) {
+        lints.push(Lint::Unsolvable { reachable: unsolvable.reachable.len() });
+    }
+    lints
+}
+
+/// cells which aren't walls with exactly one neighbor which isn't a wall either
+fn dead_ends(stage: &Stage) -> usize {
+    let open = |position: (i32, i32)| stage.tile(position).is_some_and(|tile| tile != Tile::Wall);
+    stage.cells()
+        .filter(|&(position, _)| open(position))
+        .filter(|&(position, _)| {
+            Direction::ALL.iter().filter(|&&direction| open(position.neighbor(direction))).count() == 1
+        })
+        .count()
+}
+
+/// the map with an arrow on every cell the player leaves along `solution`
+fn overlay(stage: &Stage, solution: &[Direction]) -> String {
+    let (height, width) = stage.size();
+    let mut map = (0..height)
+        .map(|y| (0..width).map(|x| stage.tile((y, x)).unwrap().to_char()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut game = Game::new(stage.clone());
+    for &direction in solution {
+        let arrow = match direction {
+            Direction::Up => '↑',
+            Direction::Down => '↓',
+            Direction::Left => '←',
+            Direction::Right => '→',
+        };
+        let from = game.player();
+        let planned = game.play(direction).expect("the solution bumps");
+        let (_, walked) = planned.path.split_last().unwrap();
+        for &(y, x) in std::iter::once(&from).chain(walked) {
+            map[y as usize][x as usize] = arrow;
+        }
+    }
+    map.iter().rev().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n")
+}
+
+fn load(path: &str) -> Result<Stage, String> {
+    Stage::load(path).map_err(|e| format!("{}: error: {}", path, e))
+}
+
+fn validate(paths: &[String]) -> Result<(), String> {
+    let mut failed = 0;
+    for path in paths {
+        let stage = match load(path) {
+            Ok(stage) => stage,
+            Err(e) => {
+                eprintln!("{}", e);
+                failed += 1;
+                continue;
+            }
+        };
+        let lints = lint(&stage);
+        for lint in &lints {
+            let level = if lint.is_error() { "error" } else { "warning" };
+            eprintln!("{}: {}: {}", path, level, lint);
+        }
+        if lints.iter().any(Lint::is_error) {
+            failed += 1;
+        } else {
+            println!("{}: ok", path);
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("{} of {} stages failed", failed, paths.len())),
+    }
+}
+
+fn print_solution(path: &str) -> Result<(), String> {
+    let stage = load(path)?;
+    let solution = solve(&stage).map_err(|e| format!("{}: error: {}", path, e))?;
+    let moves = solution.iter().map(|direction| format!("{:?}", direction)).collect::<Vec<_>>();
+    println!("{} moves: {}", solution.len(), moves.join(" "));
+    println!("{}", overlay(&stage, &solution));
+    Ok(())
+}
+
+fn stats(paths: &[String]) -> Result<(), String> {
+    let mut failed = 0;
+    for path in paths {
+        let stage = match load(path) {
+            Ok(stage) => stage,
+            Err(e) => {
+                eprintln!("{}", e);
+                failed += 1;
+                continue;
+            }
+        };
+        let (height, width) = stage.size();
+        let floor = stage.cells().filter(|&(_, tile)| tile != Tile::Wall).count();
+        let solution = match solve(&stage) {
+            Ok(solution) => format!("{} moves", solution.len()),
+            Err(_) => "none".to_string(),
+        };
+        println!("{}", path);
+        println!("    size: {}x{}", width, height);
+        println!("    floor: {} cells", floor);
+        println!("    dead ends: {}", dead_ends(&stage));
+        println!("    solution: {}", solution);
+    }
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("{} of {} stages failed to load", failed, paths.len())),
+    }
+}
+
+fn parse_generator_config(args: &[String]) -> Result<GeneratorConfig, String> {
+    let mut config = GeneratorConfig::default();
+    for option in args.chunks(2) {
+        let (name, value) = match option {
+            [name, value] => (name.as_str(), value.as_str()),
+            [name] => return Err(format!("missing value after {}", name)),
+            _ => unreachable!(),
+        };
+        let invalid = || format!("invalid value {:?} for {}", value, name);
+        match name {
+            "--algorithm" => {
+                config.algorithm = match value {
+                    "backtracker" => Algorithm::RecursiveBacktracker,
+                    "prim" => Algorithm::Prim,
+                    "kruskal" => Algorithm::Kruskal,
+                    "wilson" => Algorithm::Wilson,
+                    "eller" => Algorithm::Eller,
+                    _ => return Err(format!("unknown algorithm {:?}", value)),
+                }
+            }
+            "--width" => config.width = value.parse().map_err(|_| invalid())?,
+            "--height" => config.height = value.parse().map_err(|_| invalid())?,
+            "--braid" => config.braid = value.parse().map_err(|_| invalid())?,
+            "--seed" => config.seed = value.parse().map_err(|_| invalid())?,
+            _ => return Err(format!("unknown option {}", name)),
+        }
+    }
+    Ok(config)
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args {
+        [command, paths @ ..] if command == "validate" && !paths.is_empty() => validate(paths),
+        [command, path] if command == "solve" => print_solution(path),
+        [command, paths @ ..] if command == "stats" && !paths.is_empty() => stats(paths),
+        [command, options @ ..] if command == "generate" => {
+            println!("{}", generate(&parse_generator_config(options)?));
+            Ok(())
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lints_doors_and_keys_without_their_pair() {
+        let stage: Stage = "@aB.G".parse().unwrap();
+        assert_eq!(lint(&stage), vec![
+            Lint::KeyWithoutDoor { letter: 'a', at: (0, 1) },
+            Lint::DoorWithoutKey { letter: 'b', at: (0, 2) },
+            Lint::Unsolvable { reachable: 2 },
+        ]);
+    }
+
+    #[test]
+    fn counts_dead_ends() {
+        let stage: Stage = "@.#\n#.G\n..#".parse().unwrap();
+        assert_eq!(dead_ends(&stage), 3);
+    }
+
+    #[test]
+    fn overlays_the_solution_on_the_map() {
+        let stage: Stage = "@~.G\n....".parse().unwrap();
+        let solution = solve(&stage).unwrap();
+        assert_eq!(overlay(&stage, &solution), "....\n→→→G");
+    }
+}
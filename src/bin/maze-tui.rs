@@ -0,0 +1,155 @@
+//! Plays the stages of the manifest in a terminal, for machines which can't open a window.
+//!
+//! Usage: `maze-tui [MANIFEST]`, where the manifest defaults to `resources/stages/manifest.ron`.
+//! Stages are drawn like in the game, the first line of a stage file at the bottom.
+use std::{
+    error::Error,
+    io::{self, Write},
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute, queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use maze_game::{
+    game::{Game, Outcome},
+    grid::Direction,
+    resources::StageManifest,
+    rules::Board,
+    stage::Tile,
+};
+
+const DEFAULT_MANIFEST: &str = "resources/stages/manifest.ron";
+
+/// Raw mode on the alternate screen, left when dropped so the shell comes back even on errors.
+struct Screen;
+
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// two columns for each cell, so that cells look square
+fn cell(game: &Game, position: (i32, i32)) -> (String, Color) {
+    if position == game.player() {
+        return ("@ ".to_string(), Color::Yellow);
+    }
+    if game.hunters().contains(&position) {
+        return ("H ".to_string(), Color::Magenta);
+    }
+    if game.patrols().any(|patrol| patrol == position) {
+        return ("E ".to_string(), Color::Red);
+    }
+    let tile = game.stage().tile(position).unwrap();
+    let has_box = game.has_box(&position);
+    let (glyph, color) = match tile {
+        _ if has_box && tile.is_pad() => ('*', Color::Green),
+        _ if has_box => ('$', Color::DarkYellow),
+        Tile::Wall => return ("██".to_string(), Color::DarkGrey),
+        Tile::Goal => ('G', Color::Green),
+        Tile::Key(letter) if !game.is_collected(position) => (letter, Color::Yellow),
+        Tile::Door(letter) if !game.keys().contains(&letter) => (letter.to_ascii_uppercase(), Color::DarkMagenta),
+        Tile::Pad | Tile::BoxOnPad => ('+', Color::DarkCyan),
+        Tile::Ice => ('~', Color::Cyan),
+        Tile::Teleporter(digit) => (digit, Color::Blue),
+        Tile::Arrow(_) => (tile.to_char(), Color::White),
+        _ => ('.', Color::DarkGrey),
+    };
+    (format!("{} ", glyph), color)
+}
+
+/// the stage around the player, between a header and a footer
+fn draw(out: &mut impl Write, title: &str, game: &Game, footer: &str) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    queue!(out, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0), ResetColor)?;
+    let keys = game.keys().iter().collect::<String>();
+    queue!(out, Print(format!("{}    moves: {}    keys: {}", title, game.moves, keys)))?;
+
+    // scroll stages larger than the terminal to keep the player in view
+    let (height, width) = game.stage().size();
+    let view_width = (columns as i32 / 2).clamp(1, width);
+    let view_height = (rows as i32 - 2).clamp(1, height);
+    let (player_y, player_x) = game.player();
+    let left = (player_x - view_width / 2).clamp(0, width - view_width);
+    let bottom = (player_y - view_height / 2).clamp(0, height - view_height);
+    for row in 0..view_height {
+        let y = bottom + view_height - 1 - row;
+        queue!(out, cursor::MoveTo(0, row as u16 + 1))?;
+        for x in left..left + view_width {
+            let (glyph, color) = cell(game, (y, x));
+            queue!(out, SetForegroundColor(color), Print(glyph))?;
+        }
+    }
+
+    queue!(out, ResetColor, cursor::MoveTo(0, view_height as u16 + 1), Print(footer))?;
+    out.flush()
+}
+
+/// the next key pressed
+fn read_key() -> io::Result<KeyCode> {
+    loop {
+        if let Event::Key(KeyEvent { code, kind: KeyEventKind::Press, .. }) = event::read()? {
+            return Ok(code);
+        }
+    }
+}
+
+fn run(manifest: &StageManifest) -> Result<(), Box<dyn Error>> {
+    let _screen = Screen::enter()?;
+    let mut out = io::stdout();
+
+    let mut index = 0;
+    let mut stage = manifest.load_stage(manifest.get(index).unwrap())?;
+    let mut game = Game::new(stage.clone());
+    loop {
+        let entry = manifest.get(index).unwrap();
+        let title = format!("{:02} {} ({}/{})", index + 1, entry.name, index + 1, manifest.len());
+        let last = index + 1 == manifest.len();
+        let footer = match game.outcome {
+            Outcome::Playing => "arrows: move    r: restart    q: quit".to_string(),
+            Outcome::Caught => "Caught!    r: retry    q: quit".to_string(),
+            Outcome::Cleared if last => format!("Cleared in {} moves, that was the last stage!    q: quit", game.moves),
+            Outcome::Cleared => format!("Cleared in {} moves!    enter: next stage    q: quit", game.moves),
+        };
+        draw(&mut out, &title, &game, &footer)?;
+
+        let direction = match (read_key()?, game.outcome) {
+            (KeyCode::Char('q'), _) | (KeyCode::Esc, _) => return Ok(()),
+            (KeyCode::Char('r'), _) => {
+                game = Game::new(stage.clone());
+                continue;
+            }
+            (KeyCode::Enter, Outcome::Cleared) if !last => {
+                index += 1;
+                stage = manifest.load_stage(manifest.get(index).unwrap())?;
+                game = Game::new(stage.clone());
+                continue;
+            }
+            (KeyCode::Up, _) => Direction::Up,
+            (KeyCode::Down, _) => Direction::Down,
+            (KeyCode::Left, _) => Direction::Left,
+            (KeyCode::Right, _) => Direction::Right,
+            _ => continue,
+        };
+        game.play(direction);
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_MANIFEST.to_string());
+    let manifest = StageManifest::load(&path).map_err(|e| format!("{}: {}", path, e))?;
+    run(&manifest)
+}
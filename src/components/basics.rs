@@ -1,8 +1,10 @@
 //! Basic Components
 use amethyst::{
-    ecs::{Component, NullStorage},
+    ecs::{Component, DenseVecStorage, NullStorage},
 };
 
+use crate::components::grid2d::{Direction, Grid2D};
+
 #[derive(Default, Component)]
 #[storage(NullStorage)]
 pub struct Player;
@@ -19,6 +21,40 @@ pub struct Obstacle;
 #[storage(NullStorage)]
 pub struct Wall;
 
+/// A box pushed one cell by the player walking into it, if the cell beyond is free.
+#[derive(Default, Component)]
+#[storage(NullStorage)]
+pub struct Pushable;
+
+/// Every pad must be covered by a `Pushable` before reaching the `Goal` clears the stage.
 #[derive(Default, Component)]
 #[storage(NullStorage)]
-pub struct WallInvisible;
\ No newline at end of file
+pub struct Pad;
+
+/// Floor on which the player keeps sliding in the direction it moved,
+/// until the cell ahead is blocked or it steps off the ice.
+#[derive(Default, Component)]
+#[storage(NullStorage)]
+pub struct Ice;
+
+/// Sends the player to `partner`, the other teleporter with the same digit.
+#[derive(Clone, Debug, Component)]
+#[storage(DenseVecStorage)]
+pub struct Teleporter {
+    pub partner: Grid2D,
+}
+
+/// Arrow floor which the player can only enter or leave by moving in its direction.
+#[derive(Clone, Copy, Debug, Component)]
+#[storage(DenseVecStorage)]
+pub struct OneWay(pub Direction);
+
+/// Picked up into the player's `Inventory` by walking on it.
+#[derive(Clone, Copy, Debug, Component)]
+#[storage(DenseVecStorage)]
+pub struct Key(pub char);
+
+/// Carries `Obstacle` until the key with the same letter is in the player's `Inventory`.
+#[derive(Clone, Copy, Debug, Component)]
+#[storage(DenseVecStorage)]
+pub struct Door(pub char);
\ No newline at end of file
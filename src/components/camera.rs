@@ -0,0 +1,21 @@
+use amethyst::ecs::{Component, HashMapStorage};
+
+/// A camera showing only part of a stage too large for the screen,
+/// kept on the player by `CameraSystem`.
+#[derive(Clone, Copy, Debug, Component)]
+#[storage(HashMapStorage)]
+pub struct Scrolling {
+    /// `(height, width)` of the stage in cells
+    pub map_size: (i32, i32),
+    /// cells the camera shows across, margin included
+    pub view_size: f32,
+}
+
+/// Parent of the floor tiles, which cover the view rather than the whole stage.
+/// `CameraSystem` moves it by whole cells under a scrolling camera.
+#[derive(Clone, Copy, Debug, Component)]
+#[storage(HashMapStorage)]
+pub struct Background {
+    /// `(height, width)` of the block of tiles in cells
+    pub size: (i32, i32),
+}
@@ -0,0 +1,38 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+use crate::components::grid2d::Grid2D;
+
+/// Takes one step along its patrol each time the player moves, see `EnemySystem`.
+/// The player loses when it meets an enemy.
+#[derive(Clone, Debug, Component)]
+#[storage(DenseVecStorage)]
+pub struct Enemy {
+    /// cells of the patrol turn after turn, repeating forever
+    pub patrol: Vec<Grid2D>,
+    /// index in `patrol` of the current cell
+    pub step: usize,
+}
+
+impl Enemy {
+    pub fn new(patrol: Vec<Grid2D>) -> Self {
+        Self { patrol, step: 0 }
+    }
+
+    pub fn position(&self) -> Grid2D {
+        self.patrol[self.step % self.patrol.len()].clone()
+    }
+
+    /// index of the cell after the current one
+    pub fn next_step(&self) -> usize {
+        (self.step + 1) % self.patrol.len()
+    }
+}
+
+/// Walks `speed` cells towards the player each time it moves, once the player
+/// is at most `vision` steps away, see `EnemySystem`. The player loses when it meets a hunter.
+#[derive(Clone, Copy, Debug, Component)]
+#[storage(DenseVecStorage)]
+pub struct Hunter {
+    pub speed: u32,
+    pub vision: u32,
+}
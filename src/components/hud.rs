@@ -0,0 +1,14 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+/// Marks a `UiText` of the in-play HUD with the value it shows.
+#[derive(Clone, Copy, Debug, PartialEq, Component)]
+#[storage(DenseVecStorage)]
+pub enum HudField {
+    /// set once when the stage starts
+    StageName,
+    Moves,
+    Bumps,
+    Time,
+    /// letters of the keys in the player's `Inventory`
+    Keys,
+}
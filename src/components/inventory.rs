@@ -0,0 +1,34 @@
+use amethyst::ecs::{Component, DenseVecStorage};
+
+/// Keys collected by the `Player` on the current stage.
+#[derive(Clone, Debug, Default, Component)]
+#[storage(DenseVecStorage)]
+pub struct Inventory {
+    /// one letter per collected key, a stage may have several keys with the same letter
+    keys: Vec<char>,
+}
+
+impl Inventory {
+    pub fn has(&self, letter: char) -> bool {
+        self.keys.contains(&letter)
+    }
+
+    pub fn add(&mut self, letter: char) {
+        self.keys.push(letter);
+    }
+
+    /// give one key with `letter` back, when its pickup is undone
+    pub fn remove(&mut self, letter: char) {
+        if let Some(i) = self.keys.iter().position(|&key| key == letter) {
+            self.keys.remove(i);
+        }
+    }
+
+    /// distinct letters of the collected keys, in alphabetical order
+    pub fn letters(&self) -> Vec<char> {
+        let mut letters = self.keys.clone();
+        letters.sort_unstable();
+        letters.dedup();
+        letters
+    }
+}
@@ -1,2 +1,7 @@
 pub mod basics;
-pub mod grid2d;
\ No newline at end of file
+pub mod camera;
+pub mod enemy;
+pub mod grid2d;
+pub mod hud;
+pub mod inventory;
+pub mod tween;
\ No newline at end of file
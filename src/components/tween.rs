@@ -0,0 +1,123 @@
+//! Visual interpolation of a `Transform` between grid cells.
+//! `Grid2D` stays the position used by the rules; the sprite only catches up.
+use std::f32::consts::PI;
+
+use amethyst::{
+    core::Transform,
+    ecs::{Component, DenseVecStorage},
+};
+
+/// Curves available to `MOVE_EASING`, `BUMP_EASING` and `TELEPORT_EASING` in `config.rs`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseInQuad,
+    EaseOutQuad,
+    EaseInOutQuad,
+    EaseOutCubic,
+    EaseInOutSine,
+}
+
+impl Easing {
+    /// map the progress `t` in `[0, 1]` to the eased progress
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInQuad => t * t,
+            Easing::EaseOutQuad => t * (2. - t),
+            Easing::EaseInOutQuad => {
+                if t < 0.5 { 2. * t * t } else { 1. - 2. * (1. - t) * (1. - t) }
+            }
+            Easing::EaseOutCubic => 1. - (1. - t).powi(3),
+            Easing::EaseInOutSine => (1. - (PI * t).cos()) / 2.,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TweenKind {
+    /// go from `from` to `to` and stay there
+    Slide,
+    /// go from `from` towards `to` and come back
+    Bump,
+    /// shrink away at `from`, then grow back at `to`
+    Teleport,
+}
+
+#[derive(Clone, Debug, Component)]
+#[storage(DenseVecStorage)]
+pub struct Tween {
+    pub kind: TweenKind,
+    pub from: (f32, f32),
+    pub to: (f32, f32),
+    pub easing: Easing,
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+impl Tween {
+    /// slide from where `transform` is now to `to`
+    pub fn slide(transform: &Transform, to: (f32, f32), duration: f32, easing: Easing) -> Self {
+        let translation = transform.translation();
+        Self {
+            kind: TweenKind::Slide,
+            from: (translation.x, translation.y),
+            to,
+            easing,
+            duration,
+            elapsed: 0.,
+        }
+    }
+
+    /// push from `at` towards `towards` and come back to `at`
+    pub fn bump(at: (f32, f32), towards: (f32, f32), duration: f32, easing: Easing) -> Self {
+        Self {
+            kind: TweenKind::Bump,
+            from: at,
+            to: towards,
+            easing,
+            duration,
+            elapsed: 0.,
+        }
+    }
+
+    /// vanish from where `transform` is now and appear at `to`
+    pub fn teleport(transform: &Transform, to: (f32, f32), duration: f32, easing: Easing) -> Self {
+        Self {
+            kind: TweenKind::Teleport,
+            ..Self::slide(transform, to, duration, easing)
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn progress(&self) -> f32 {
+        let t = if self.duration > 0. { self.elapsed / self.duration } else { 1. };
+        self.easing.apply(t)
+    }
+
+    /// `(x, y)` where the sprite should be drawn now
+    pub fn position(&self) -> (f32, f32) {
+        let progress = match self.kind {
+            TweenKind::Slide => self.progress(),
+            TweenKind::Bump => 1. - (2. * self.progress() - 1.).abs(),
+            TweenKind::Teleport if self.progress() < 0.5 => 0.,
+            TweenKind::Teleport => 1.,
+        };
+        (
+            self.from.0 + (self.to.0 - self.from.0) * progress,
+            self.from.1 + (self.to.1 - self.from.1) * progress,
+        )
+    }
+
+    /// scale of the sprite, which only changes while teleporting
+    pub fn scale(&self) -> f32 {
+        match self.kind {
+            TweenKind::Teleport => (2. * self.progress() - 1.).abs(),
+            TweenKind::Slide | TweenKind::Bump => 1.,
+        }
+    }
+}
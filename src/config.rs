@@ -1,8 +1,40 @@
 //! Define constants which are widely used in the crate.
 
+use crate::components::tween::Easing;
+
 /// arina size definition
 pub const CELL_SIZE: f32 = 16.;
 pub const DEFAULT_GRID_SIZE: f32 = 12.;
+/// cells the camera shows at most across, larger stages scroll with the player
+pub const MAX_VIEW_SIZE: f32 = 32.;
+
+/// layouts the solver searches at most for the shortest solution shown on the clear screen
+pub const SOLVER_BUDGET: usize = 200_000;
 
 /// frames within which the player can't move after a move
-pub const FREEZE_TIME: u32 = 10;
\ No newline at end of file
+pub const FREEZE_TIME: u32 = 10;
+
+/// seconds the player sprite takes to slide to the next cell
+pub const MOVE_DURATION: f32 = 0.12;
+pub const MOVE_EASING: Easing = Easing::EaseOutQuad;
+/// seconds of the bump against an obstacle, and how far it goes in cells
+pub const BUMP_DURATION: f32 = 0.12;
+pub const BUMP_DISTANCE: f32 = 0.25;
+pub const BUMP_EASING: Easing = Easing::EaseInOutSine;
+/// seconds the player takes to vanish from a teleporter and appear on its partner
+pub const TELEPORT_DURATION: f32 = 0.3;
+pub const TELEPORT_EASING: Easing = Easing::EaseInOutQuad;
+
+/// names of the actions bound in `resources/input.ron`
+pub mod actions {
+    pub const UP: &str = "up";
+    pub const DOWN: &str = "down";
+    pub const LEFT: &str = "left";
+    pub const RIGHT: &str = "right";
+    pub const UNDO: &str = "undo";
+    pub const REDO: &str = "redo";
+    pub const RESTART: &str = "restart";
+    pub const TITLE: &str = "title";
+    pub const PAUSE: &str = "pause";
+    pub const CONFIRM: &str = "confirm";
+}
@@ -0,0 +1,183 @@
+//! A stage played outside of the ECS, moved by the same `rules` as the systems of the game.
+use std::collections::HashSet;
+
+use crate::{
+    grid::Direction,
+    rules::{hunt, is_caught, is_cleared, plan_move, Board, Move},
+    stage::{Stage, Tile},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Playing,
+    Cleared,
+    /// an enemy caught the player
+    Caught,
+}
+
+/// Everything a move can change on the stage, like the entities of `PlayState`.
+pub struct Game {
+    stage: Stage,
+    player: (i32, i32),
+    /// letters of the collected keys, in the order they were collected
+    keys: Vec<char>,
+    /// cells of the collected keys
+    collected: HashSet<(i32, i32)>,
+    boxes: HashSet<(i32, i32)>,
+    /// positions of the hunters, in the order of the manifest
+    hunters: Vec<(i32, i32)>,
+    pub moves: usize,
+    pub outcome: Outcome,
+}
+
+impl Game {
+    pub fn new(stage: Stage) -> Self {
+        let boxes = stage.positions_of(Tile::has_box).into_iter().collect();
+        let hunters = stage.hunters().iter().map(|hunter| hunter.start).collect();
+        Self {
+            player: stage.player(),
+            stage,
+            keys: Vec::new(),
+            collected: HashSet::new(),
+            boxes,
+            hunters,
+            moves: 0,
+            outcome: Outcome::Playing,
+        }
+    }
+
+    pub fn stage(&self) -> &Stage {
+        &self.stage
+    }
+
+    pub fn player(&self) -> (i32, i32) {
+        self.player
+    }
+
+    pub fn keys(&self) -> &[char] {
+        &self.keys
+    }
+
+    pub fn is_collected(&self, cell: (i32, i32)) -> bool {
+        self.collected.contains(&cell)
+    }
+
+    /// current cells of the enemies following a patrol, which step once per move
+    pub fn patrols(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.stage.patrols().iter().map(move |cycle| cycle[self.moves % cycle.len()])
+    }
+
+    pub fn hunters(&self) -> &[(i32, i32)] {
+        &self.hunters
+    }
+
+    /// move the player towards `direction`, then the enemies.
+    /// Returns the move of the player, `None` if it bumped or the stage is over.
+    pub fn play(&mut self, direction: Direction) -> Option<Move<(i32, i32)>> {
+        if self.outcome != Outcome::Playing {
+            return None;
+        }
+        let planned = plan_move(self, &self.player, direction)?;
+
+        for &cell in &planned.path {
+            if let Some(Tile::Key(letter)) = self.stage.tile(cell) {
+                if self.collected.insert(cell) {
+                    self.keys.push(letter);
+                }
+            }
+        }
+        if let Some((from, to)) = planned.pushed {
+            self.boxes.remove(&from);
+            self.boxes.insert(to);
+        }
+        self.player = *planned.end();
+        self.moves += 1;
+
+        let mut caught = self.stage.patrols().iter().any(|cycle| {
+            let before = cycle[(self.moves - 1) % cycle.len()];
+            let after = cycle[self.moves % cycle.len()];
+            is_caught(&self.player, &before, &after)
+        });
+        let hunters = self.hunters.iter()
+            .zip(self.stage.hunters())
+            .map(|(from, hunter)| {
+                let to = hunt(self, from, &self.player, hunter.speed, hunter.vision);
+                caught |= is_caught(&self.player, from, &to);
+                to
+            })
+            .collect();
+        self.hunters = hunters;
+
+        let goals = self.stage.positions_of(|tile| tile == Tile::Goal);
+        let pads = self.stage.positions_of(Tile::is_pad);
+        self.outcome = if caught {
+            Outcome::Caught
+        } else if is_cleared(self, &self.player, &goals, &pads) {
+            Outcome::Cleared
+        } else {
+            Outcome::Playing
+        };
+        Some(planned)
+    }
+}
+
+impl Board<(i32, i32)> for Game {
+    fn is_blocked(&self, &cell: &(i32, i32)) -> bool {
+        match self.stage.tile(cell) {
+            Some(Tile::Door(letter)) => !self.keys.contains(&letter),
+            Some(tile) => tile.is_obstacle(),
+            None => true,
+        }
+    }
+
+    fn has_box(&self, cell: &(i32, i32)) -> bool {
+        self.boxes.contains(cell)
+    }
+
+    fn has_key(&self, &cell: &(i32, i32)) -> bool {
+        matches!(self.stage.tile(cell), Some(Tile::Key(_))) && !self.is_collected(cell)
+    }
+
+    fn is_ice(&self, &cell: &(i32, i32)) -> bool {
+        self.stage.tile(cell) == Some(Tile::Ice)
+    }
+
+    fn allows(&self, &cell: &(i32, i32), direction: Direction) -> bool {
+        self.stage.tile(cell).is_some_and(|tile| tile.allows(direction))
+    }
+
+    fn teleporter_partner(&self, &cell: &(i32, i32)) -> Option<(i32, i32)> {
+        self.stage.teleporter_partner(cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{resources::StageManifest, stage::{solve, Hunter}};
+
+    #[test]
+    fn the_solution_of_every_shipped_stage_clears_it() {
+        let manifest = StageManifest::load("resources/stages/manifest.ron").unwrap();
+        for i in 0..manifest.len() {
+            let entry = manifest.get(i).unwrap();
+            let stage = manifest.load_stage(entry).unwrap();
+            let solution = solve(&stage).unwrap();
+            let mut game = Game::new(stage);
+            for direction in solution {
+                assert!(game.play(direction).is_some(), "stage {:?}: the solution bumps", entry.id);
+            }
+            assert_eq!(game.outcome, Outcome::Cleared, "stage {:?}", entry.id);
+        }
+    }
+
+    #[test]
+    fn enemies_catch_the_player() {
+        let stage: Stage = "@.G".parse().unwrap();
+        let hunter = Hunter { start: (0, 2), speed: 1, vision: u32::MAX };
+        let mut game = Game::new(stage.with_hunters(&[hunter]).unwrap());
+        assert!(game.play(Direction::Right).is_some());
+        assert_eq!(game.outcome, Outcome::Caught);
+        assert!(game.play(Direction::Right).is_none());
+    }
+}
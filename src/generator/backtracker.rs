@@ -0,0 +1,30 @@
+//! Recursive backtracker: a randomized depth-first search, giving long winding corridors.
+use rand::{seq::SliceRandom, Rng};
+use rand_pcg::Pcg64;
+
+use super::Maze;
+
+pub(super) fn carve(maze: &mut Maze, rng: &mut Pcg64) {
+    let mut visited = vec![false; maze.len()];
+    let start = rng.gen_range(0, maze.len());
+    visited[start] = true;
+    let mut stack = vec![start];
+
+    while let Some(&cell) = stack.last() {
+        let unvisited = maze.neighbors(cell)
+            .into_iter()
+            .map(|(next, _)| next)
+            .filter(|&next| !visited[next])
+            .collect::<Vec<_>>();
+        match unvisited.choose(rng) {
+            Some(&next) => {
+                maze.link(cell, next);
+                visited[next] = true;
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Eller: build the maze one row at a time, keeping only the sets of the current row.
+use std::collections::BTreeMap;
+
+use rand::{seq::SliceRandom, Rng};
+use rand_pcg::Pcg64;
+
+use super::Maze;
+
+pub(super) fn carve(maze: &mut Maze, rng: &mut Pcg64) {
+    let width = maze.width;
+    let mut sets = vec![0usize; width];
+    let mut next_set = 1;
+
+    for y in 0..maze.height {
+        let last_row = y + 1 == maze.height;
+        for set in sets.iter_mut().filter(|set| **set == 0) {
+            *set = next_set;
+            next_set += 1;
+        }
+
+        // join adjacent cells of different sets, all of them on the last row
+        for x in 0..width - 1 {
+            if sets[x] != sets[x + 1] && (last_row || rng.gen_bool(0.5)) {
+                maze.link(y * width + x, y * width + x + 1);
+                let (from, to) = (sets[x + 1], sets[x]);
+                for set in sets.iter_mut().filter(|set| **set == from) {
+                    *set = to;
+                }
+            }
+        }
+        if last_row {
+            break;
+        }
+
+        // every set goes down at least once
+        let mut members = BTreeMap::<usize, Vec<usize>>::new();
+        for (x, &set) in sets.iter().enumerate() {
+            members.entry(set).or_default().push(x);
+        }
+        let mut below = vec![0; width];
+        for (set, mut xs) in members {
+            xs.shuffle(rng);
+            for (i, x) in xs.into_iter().enumerate() {
+                if i == 0 || rng.gen_bool(0.5) {
+                    maze.link(y * width + x, (y + 1) * width + x);
+                    below[x] = set;
+                }
+            }
+        }
+        sets = below;
+    }
+}
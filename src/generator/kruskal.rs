@@ -0,0 +1,56 @@
+//! Randomized Kruskal: join random walls between disjoint sets of cells.
+use rand::seq::SliceRandom;
+use rand_pcg::Pcg64;
+
+use super::{Maze, EAST, SOUTH};
+
+pub(super) fn carve(maze: &mut Maze, rng: &mut Pcg64) {
+    let mut edges = (0..maze.len())
+        .flat_map(|cell| {
+            maze.neighbors(cell)
+                .into_iter()
+                .filter(|&(_, side)| side == SOUTH || side == EAST)
+                .map(move |(next, _)| (cell, next))
+        })
+        .collect::<Vec<_>>();
+    edges.shuffle(rng);
+
+    let mut sets = DisjointSet::new(maze.len());
+    for (a, b) in edges {
+        if sets.union(a, b) {
+            maze.link(a, b);
+        }
+    }
+}
+
+struct DisjointSet {
+    parent: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self { parent: (0..len).collect() }
+    }
+
+    fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        root
+    }
+
+    /// merge the sets of `a` and `b`, returns `false` if they were already the same
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        self.parent[b] = a;
+        true
+    }
+}
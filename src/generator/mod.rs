@@ -0,0 +1,292 @@
+//! Procedural maze generation.
+//!
+//! A maze is a `width` x `height` grid of cells joined by passages.
+//! It is turned into a stage of `2 * width - 1` x `2 * height - 1` tiles where cells sit
+//! on even coordinates and the tiles between them are either a passage or a wall.
+//! The stage has no outer wall since the rules block the player at the edges of every stage.
+use std::collections::VecDeque;
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_pcg::Pcg64;
+use serde::Deserialize;
+
+use crate::stage::{Stage, Tile};
+
+mod backtracker;
+mod eller;
+mod kruskal;
+mod prim;
+mod wilson;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    Eller,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GeneratorConfig {
+    pub algorithm: Algorithm,
+    /// number of cells in a row, at least 2
+    pub width: usize,
+    /// number of cells in a column, at least 2
+    pub height: usize,
+    /// probability in `[0, 1]` to remove each dead end by opening a loop
+    pub braid: f64,
+    /// the same seed and config always give the same stage
+    pub seed: u64,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::RecursiveBacktracker,
+            width: 6,
+            height: 6,
+            braid: 0.,
+            seed: 0,
+        }
+    }
+}
+
+/// Generate a stage. The player starts in a corner and the goal is
+/// the cell farthest from it.
+pub fn generate(config: &GeneratorConfig) -> Stage {
+    carve(config).to_stage()
+}
+
+fn carve(config: &GeneratorConfig) -> Maze {
+    let mut rng = Pcg64::seed_from_u64(config.seed);
+    let mut maze = Maze::new(config.width.max(2), config.height.max(2));
+
+    match config.algorithm {
+        Algorithm::RecursiveBacktracker => backtracker::carve(&mut maze, &mut rng),
+        Algorithm::Prim => prim::carve(&mut maze, &mut rng),
+        Algorithm::Kruskal => kruskal::carve(&mut maze, &mut rng),
+        Algorithm::Wilson => wilson::carve(&mut maze, &mut rng),
+        Algorithm::Eller => eller::carve(&mut maze, &mut rng),
+    }
+    braid(&mut maze, config.braid.clamp(0., 1.), &mut rng);
+    maze
+}
+
+const NORTH: u8 = 1;
+const SOUTH: u8 = 2;
+const EAST: u8 = 4;
+const WEST: u8 = 8;
+
+/// Cells indexed by `y * width + x`, each holding a bit set of its open sides.
+struct Maze {
+    width: usize,
+    height: usize,
+    links: Vec<u8>,
+}
+
+impl Maze {
+    fn new(width: usize, height: usize) -> Self {
+        Self { width, height, links: vec![0; width * height] }
+    }
+
+    fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    /// adjacent cells with the side of `cell` they are on
+    fn neighbors(&self, cell: usize) -> Vec<(usize, u8)> {
+        let (y, x) = (cell / self.width, cell % self.width);
+        let mut neighbors = Vec::with_capacity(4);
+        if y > 0 {
+            neighbors.push((cell - self.width, NORTH));
+        }
+        if y + 1 < self.height {
+            neighbors.push((cell + self.width, SOUTH));
+        }
+        if x + 1 < self.width {
+            neighbors.push((cell + 1, EAST));
+        }
+        if x > 0 {
+            neighbors.push((cell - 1, WEST));
+        }
+        neighbors
+    }
+
+    /// open a passage between two adjacent cells
+    fn link(&mut self, a: usize, b: usize) {
+        let side = match b as isize - a as isize {
+            d if d == -(self.width as isize) => NORTH,
+            d if d == self.width as isize => SOUTH,
+            1 => EAST,
+            -1 => WEST,
+            _ => panic!("cells {} and {} are not adjacent", a, b),
+        };
+        let opposite = match side {
+            NORTH => SOUTH,
+            SOUTH => NORTH,
+            EAST => WEST,
+            _ => EAST,
+        };
+        self.links[a] |= side;
+        self.links[b] |= opposite;
+    }
+
+    fn is_linked(&self, cell: usize, side: u8) -> bool {
+        self.links[cell] & side != 0
+    }
+
+    fn degree(&self, cell: usize) -> u32 {
+        self.links[cell].count_ones()
+    }
+
+    /// cells reachable from `cell` through a passage
+    fn linked(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        self.neighbors(cell)
+            .into_iter()
+            .filter(move |&(_, side)| self.is_linked(cell, side))
+            .map(|(next, _)| next)
+    }
+
+    /// the cell with the longest path from `start`
+    fn farthest_from(&self, start: usize) -> usize {
+        let mut distance = vec![None; self.len()];
+        let mut queue = VecDeque::new();
+        distance[start] = Some(0);
+        queue.push_back(start);
+        let mut farthest = start;
+
+        while let Some(cell) = queue.pop_front() {
+            farthest = cell;
+            let d = distance[cell].unwrap();
+            for next in self.linked(cell).collect::<Vec<_>>() {
+                if distance[next].is_none() {
+                    distance[next] = Some(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        farthest
+    }
+
+    fn to_stage(&self) -> Stage {
+        let mut tiles = vec![vec![Tile::Wall; 2 * self.width - 1]; 2 * self.height - 1];
+        for cell in 0..self.len() {
+            let (y, x) = (2 * (cell / self.width), 2 * (cell % self.width));
+            tiles[y][x] = Tile::Empty;
+            if self.is_linked(cell, SOUTH) {
+                tiles[y + 1][x] = Tile::Empty;
+            }
+            if self.is_linked(cell, EAST) {
+                tiles[y][x + 1] = Tile::Empty;
+            }
+        }
+
+        let goal = self.farthest_from(0);
+        tiles[0][0] = Tile::Player;
+        tiles[2 * (goal / self.width)][2 * (goal % self.width)] = Tile::Goal;
+
+        Stage::from_tiles(tiles).expect("generated maze is always a valid stage")
+    }
+}
+
+/// Remove dead ends with probability `braid` by linking them to another neighbor,
+/// preferring neighbors which are dead ends too.
+fn braid(maze: &mut Maze, braid: f64, rng: &mut Pcg64) {
+    if braid <= 0. {
+        return;
+    }
+    let mut dead_ends = (0..maze.len()).filter(|&cell| maze.degree(cell) == 1).collect::<Vec<_>>();
+    dead_ends.shuffle(rng);
+
+    for cell in dead_ends {
+        if maze.degree(cell) != 1 || !rng.gen_bool(braid) {
+            continue;
+        }
+        let closed = maze.neighbors(cell)
+            .into_iter()
+            .filter(|&(_, side)| !maze.is_linked(cell, side))
+            .map(|(next, _)| next)
+            .collect::<Vec<_>>();
+        let preferred = closed.iter().copied().filter(|&next| maze.degree(next) == 1).collect::<Vec<_>>();
+        let candidates = if preferred.is_empty() { &closed } else { &preferred };
+        if let Some(&next) = candidates.choose(rng) {
+            maze.link(cell, next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stage::solve;
+
+    const ALGORITHMS: [Algorithm; 5] = [
+        Algorithm::RecursiveBacktracker,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::Wilson,
+        Algorithm::Eller,
+    ];
+
+    fn config(algorithm: Algorithm, braid: f64, seed: u64) -> GeneratorConfig {
+        GeneratorConfig { algorithm, width: 9, height: 7, braid, seed }
+    }
+
+    fn dead_ends(maze: &Maze) -> usize {
+        (0..maze.len()).filter(|&cell| maze.degree(cell) == 1).count()
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_maze() {
+        for &algorithm in &ALGORITHMS {
+            let first = carve(&config(algorithm, 0.5, 42));
+            assert_eq!(first.links, carve(&config(algorithm, 0.5, 42)).links, "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn mazes_are_perfect() {
+        for &algorithm in &ALGORITHMS {
+            for seed in 0..5 {
+                let maze = carve(&config(algorithm, 0., seed));
+                let edges = (0..maze.len()).map(|cell| maze.degree(cell) as usize).sum::<usize>() / 2;
+                assert_eq!(edges, maze.len() - 1, "{:?} seed {}", algorithm, seed);
+
+                let mut reached = vec![false; maze.len()];
+                let mut stack = vec![0];
+                reached[0] = true;
+                while let Some(cell) = stack.pop() {
+                    for next in maze.linked(cell).collect::<Vec<_>>() {
+                        if !reached[next] {
+                            reached[next] = true;
+                            stack.push(next);
+                        }
+                    }
+                }
+                assert!(reached.iter().all(|&reached| reached), "{:?} seed {}", algorithm, seed);
+            }
+        }
+    }
+
+    #[test]
+    fn braiding_removes_dead_ends() {
+        for &algorithm in &ALGORITHMS {
+            let perfect = dead_ends(&carve(&config(algorithm, 0., 7)));
+            let braided = dead_ends(&carve(&config(algorithm, 1., 7)));
+            assert!(braided < perfect, "{:?}: {} dead ends, {} once braided", algorithm, perfect, braided);
+        }
+    }
+
+    #[test]
+    fn generated_stages_are_solvable() {
+        for &algorithm in &ALGORITHMS {
+            for &braid in &[0., 0.5] {
+                let stage = carve(&config(algorithm, braid, 3)).to_stage();
+                assert_eq!(stage.size(), (13, 17));
+                assert!(solve(&stage).is_ok(), "{:?} braid {}", algorithm, braid);
+            }
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Randomized Prim: grow the maze from a random frontier cell, giving many short dead ends.
+use rand::{seq::SliceRandom, Rng};
+use rand_pcg::Pcg64;
+
+use super::Maze;
+
+pub(super) fn carve(maze: &mut Maze, rng: &mut Pcg64) {
+    let mut in_maze = vec![false; maze.len()];
+    let mut in_frontier = vec![false; maze.len()];
+    let mut frontier = Vec::new();
+
+    let start = rng.gen_range(0, maze.len());
+    in_maze[start] = true;
+    for (next, _) in maze.neighbors(start) {
+        in_frontier[next] = true;
+        frontier.push(next);
+    }
+
+    while !frontier.is_empty() {
+        let cell = frontier.swap_remove(rng.gen_range(0, frontier.len()));
+        let neighbors = maze.neighbors(cell);
+        let connected = neighbors.iter()
+            .map(|&(next, _)| next)
+            .filter(|&next| in_maze[next])
+            .collect::<Vec<_>>();
+        let &target = connected.choose(rng).expect("frontier cells touch the maze");
+        maze.link(cell, target);
+        in_maze[cell] = true;
+
+        for (next, _) in neighbors {
+            if !in_maze[next] && !in_frontier[next] {
+                in_frontier[next] = true;
+                frontier.push(next);
+            }
+        }
+    }
+}
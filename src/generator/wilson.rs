@@ -0,0 +1,35 @@
+//! Wilson: loop-erased random walks, giving a uniformly random spanning tree.
+use rand::{seq::SliceRandom, Rng};
+use rand_pcg::Pcg64;
+
+use super::Maze;
+
+pub(super) fn carve(maze: &mut Maze, rng: &mut Pcg64) {
+    let mut in_maze = vec![false; maze.len()];
+    in_maze[rng.gen_range(0, maze.len())] = true;
+
+    let mut cells = (0..maze.len()).collect::<Vec<_>>();
+    cells.shuffle(rng);
+    // the cell the walk last left each cell towards; revisiting a cell overwrites it,
+    // which erases the loop
+    let mut exit = vec![0; maze.len()];
+
+    for start in cells {
+        if in_maze[start] {
+            continue;
+        }
+        let mut cell = start;
+        while !in_maze[cell] {
+            let &(next, _) = maze.neighbors(cell).choose(rng).unwrap();
+            exit[cell] = next;
+            cell = next;
+        }
+
+        let mut cell = start;
+        while !in_maze[cell] {
+            in_maze[cell] = true;
+            maze.link(cell, exit[cell]);
+            cell = exit[cell];
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Integer positions on the grid of a stage, and the steps between them.
+use std::{
+    hash::Hash,
+    ops::{Add, AddAssign, Neg, Sub},
+};
+
+/// Position of an entity on the grid, `x` to the right and `y` upwards on screen.
+/// It is attached to most entities, see `components::grid2d` for its storage.
+/// ```
+/// # use maze_game::grid::Grid2D;
+/// assert_eq!(std::mem::size_of::<Grid2D>(), 8);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Grid2D {
+    x: i32,
+    y: i32,
+}
+
+impl Grid2D {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self{x, y}
+    }
+
+    pub fn x(&self) -> i32 {
+        self.x
+    }
+
+    pub fn y(&self) -> i32 {
+        self.y
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Grid2DDelta{
+    x: i32,
+    y: i32
+}
+
+impl Grid2DDelta {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self{x, y}
+    }
+}
+
+/// One step on the grid, as given by the arrow keys.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Down, Direction::Left, Direction::Right];
+
+    /// `(y, x)` offset of the step, in the same order as stage positions
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (1, 0),
+            Direction::Down => (-1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        }
+    }
+
+    pub fn delta(self) -> Grid2DDelta {
+        let (y, x) = self.offset();
+        Grid2DDelta::new(x, y)
+    }
+}
+
+impl Add<Grid2DDelta> for Grid2D {
+    type Output = Grid2D;
+    fn add(self, rhs: Grid2DDelta) -> Self::Output {
+        Grid2D::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Grid2DDelta> for Grid2D {
+    fn add_assign(&mut self, rhs: Grid2DDelta) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+/// the delta which moves `rhs` onto `self`
+impl Sub for Grid2D {
+    type Output = Grid2DDelta;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Grid2DDelta::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Neg for Grid2DDelta {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Grid2DDelta::new(-self.x, -self.y)
+    }
+}
+
+impl Add for Grid2DDelta {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Grid2DDelta::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+/// A cell the move rules can step from, see `rules`.
+/// The systems use `Grid2D`, the stage tools use `(y, x)` stage positions.
+pub trait Position: Clone + Eq + Hash {
+    /// the cell next to this one towards `direction`
+    fn neighbor(&self, direction: Direction) -> Self;
+}
+
+impl Position for Grid2D {
+    fn neighbor(&self, direction: Direction) -> Self {
+        self.clone() + direction.delta()
+    }
+}
+
+impl Position for (i32, i32) {
+    fn neighbor(&self, direction: Direction) -> Self {
+        let (dy, dx) = direction.offset();
+        (self.0 + dy, self.1 + dx)
+    }
+}
@@ -0,0 +1,20 @@
+//! Maze game built on Amethyst.
+//!
+//! The core of the game doesn't depend on Amethyst: `grid` for positions on the grid,
+//! `stage` for the stage files and their enemies, `generator` for generated mazes,
+//! `rules` for the moves and the win detection and `game` to play a stage with them,
+//! so tools and tests can play stages headlessly. The `components`, `resources`,
+//! `systems` and `states` modules adapt that core to the ECS and the windows of the game.
+pub mod components;
+pub mod config;
+pub mod game;
+pub mod generator;
+pub mod grid;
+pub mod resources;
+pub mod rules;
+pub mod stage;
+pub mod states;
+pub mod systems;
+
+#[cfg(test)]
+mod test_support;
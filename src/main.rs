@@ -12,32 +12,30 @@ use amethyst::{
     utils::application_root_dir,
 };
 
-use crate::systems::{
-    WinSystem, PlayerMoveSystem
+use maze_game::{
+    resources::{SaveData, Solutions, StageManifest, StagePreviews},
+    states,
+    systems::{CameraSystem, DoorSystem, EnemySystem, HudSystem, WinSystem, PlayerMoveSystem, TweenSystem, UndoSystem},
 };
 
-mod components;
-mod config;
-mod states;
-mod systems;
-mod resources;
-
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
 
     let app_root = application_root_dir()?;
     let resources = app_root.join("resources");
     let display_config = resources.join("display.ron");
+    let manifest = StageManifest::load(resources.join("stages").join("manifest.ron"))?;
+    let save_data = SaveData::load();
 
-    // let input_bundle = InputBundle::<StringBindings>::new()
-    //     .with_bindings_from_file(resources.join("input.ron"))?;
+    let input_bundle = InputBundle::<StringBindings>::new()
+        .with_bindings_from_file(resources.join("input.ron"))?;
 
-    let color: [f32; 4] = Srgba::new(44. / 255., 38. / 255., 56. / 255., 255. / 255.)
+    let color: [f32; 4] = Srgba::new(44. / 255., 38. / 255., 56. / 255., 1.)
         .into_linear()
         .into_raw();
 
     let game_data = GameDataBuilder::new()
-        .with_bundle(InputBundle::<StringBindings>::new())?
+        .with_bundle(input_bundle)?
         .with_bundle(
             RenderingBundle::<DefaultBackend>::new()
                 .with_plugin(
@@ -50,9 +48,20 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(TransformBundle::new())?
         .with_bundle(UiBundle::<StringBindings>::new())?
         .with(PlayerMoveSystem::default(), "player_move_system", &["input_system"])
-        .with(WinSystem, "win_system", &["input_system", "player_move_system"]);
+        .with(EnemySystem, "enemy_system", &["player_move_system"])
+        .with(UndoSystem::default(), "undo_system", &["input_system", "player_move_system", "enemy_system"])
+        .with(DoorSystem, "door_system", &["player_move_system", "undo_system"])
+        .with(WinSystem, "win_system", &["input_system", "player_move_system", "enemy_system", "undo_system"])
+        .with(TweenSystem, "tween_system", &["player_move_system", "enemy_system", "undo_system"])
+        .with(CameraSystem, "camera_system", &["tween_system"])
+        .with(HudSystem, "hud_system", &["player_move_system", "undo_system", "win_system"]);
 
-    let mut game = Application::new(resources, states::LoadState, game_data)?;
+    let mut game = Application::build(resources, states::LoadState)?
+        .with_resource(manifest)
+        .with_resource(save_data)
+        .with_resource(Solutions::default())
+        .with_resource(StagePreviews::default())
+        .build(game_data)?;
     game.run();
 
     Ok(())
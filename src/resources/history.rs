@@ -0,0 +1,81 @@
+//! Record of the turns played on the current stage, for undo and redo.
+use amethyst::ecs::Entity;
+
+use crate::components::grid2d::{Grid2D, Grid2DDelta};
+
+/// A reversible change of the stage made during a turn.
+#[derive(Clone, Debug)]
+pub enum Change {
+    /// `entity` moved by `delta` on the grid
+    Moved { entity: Entity, delta: Grid2DDelta },
+    /// `player` picked up the `key` entity with `letter`, which was at `at`
+    Collected { player: Entity, key: Entity, letter: char, at: Grid2D },
+    /// the `key` went back to `at`, out of the inventory of `player`
+    Dropped { player: Entity, key: Entity, letter: char, at: Grid2D },
+    /// the enemy `entity` went from step `from` of its patrol to step `to`
+    Patrolled { entity: Entity, from: usize, to: usize },
+}
+
+impl Change {
+    /// the change which cancels this one
+    pub fn reverse(&self) -> Self {
+        match self {
+            Change::Moved { entity, delta } => Change::Moved { entity: *entity, delta: -delta.clone() },
+            Change::Collected { player, key, letter, at } => {
+                Change::Dropped { player: *player, key: *key, letter: *letter, at: at.clone() }
+            }
+            Change::Dropped { player, key, letter, at } => {
+                Change::Collected { player: *player, key: *key, letter: *letter, at: at.clone() }
+            }
+            Change::Patrolled { entity, from, to } => Change::Patrolled { entity: *entity, from: *to, to: *from },
+        }
+    }
+}
+
+/// Every change made by a single player move, in the order they happened.
+pub type Turn = Vec<Change>;
+
+/// Turns of the current stage. Reset whenever a stage starts.
+#[derive(Default)]
+pub struct MoveHistory {
+    done: Vec<Turn>,
+    undone: Vec<Turn>,
+    /// a turn was recorded and the systems reacting to player moves haven't run yet
+    new_turn: bool,
+}
+
+impl MoveHistory {
+    /// record a new turn, which drops the turns that could be redone
+    pub fn record(&mut self, turn: Turn) {
+        self.done.push(turn);
+        self.undone.clear();
+        self.new_turn = true;
+    }
+
+    /// whether a turn was recorded since the last call
+    pub fn take_new_turn(&mut self) -> bool {
+        std::mem::replace(&mut self.new_turn, false)
+    }
+
+    /// add the consequences of the last player move to its turn
+    pub fn amend(&mut self, changes: Turn) {
+        if let Some(turn) = self.done.last_mut() {
+            turn.extend(changes);
+        }
+    }
+
+    /// changes to apply to cancel the last turn, latest change first
+    pub fn undo(&mut self) -> Option<Turn> {
+        let turn = self.done.pop()?;
+        let reversed = turn.iter().rev().map(Change::reverse).collect();
+        self.undone.push(turn);
+        Some(reversed)
+    }
+
+    /// changes to apply to play the last undone turn again
+    pub fn redo(&mut self) -> Option<Turn> {
+        let turn = self.undone.pop()?;
+        self.done.push(turn.clone());
+        Some(turn)
+    }
+}
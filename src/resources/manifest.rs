@@ -0,0 +1,194 @@
+//! List of the stages shipped with the game, read from `resources/stages/manifest.ron`.
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::{
+    generator::{generate, GeneratorConfig},
+    stage::{Hunter, Patrol, Stage, StageLoadError},
+};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StageEntry {
+    /// unique and stable identifier of the stage
+    pub id: String,
+    /// name shown to the player
+    pub name: String,
+    pub chapter: String,
+    pub source: StageSource,
+    /// routes of the enemies of the stage
+    #[serde(default)]
+    pub enemies: Vec<Patrol>,
+    /// enemies of the stage chasing the player
+    #[serde(default)]
+    pub hunters: Vec<Hunter>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum StageSource {
+    /// stage file, relative to the manifest
+    File(PathBuf),
+    /// maze built by the generator each time the stage is loaded
+    Generated(GeneratorConfig),
+}
+
+/// chapter of the stage files found next to the manifest but not listed in it
+const EXTRA_CHAPTER: &str = "Extra";
+
+/// Stages in the order they are played.
+#[derive(Clone, Debug, Deserialize)]
+pub struct StageManifest {
+    stages: Vec<StageEntry>,
+    /// directory containing the manifest, stage paths are resolved against it
+    #[serde(skip)]
+    directory: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(std::io::Error),
+    Ron(ron::de::Error),
+    NoStage,
+    DuplicateId(String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io(e) => write!(f, "failed to read stage manifest: {}", e),
+            ManifestError::Ron(e) => write!(f, "invalid stage manifest: {}", e),
+            ManifestError::NoStage => write!(f, "stage manifest lists no stage"),
+            ManifestError::DuplicateId(id) => write!(f, "stage id {:?} appears twice in the manifest", id),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl StageManifest {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(ManifestError::Io)?;
+        let mut manifest: StageManifest = ron::de::from_str(&source).map_err(ManifestError::Ron)?;
+        manifest.directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        manifest.scan_directory().map_err(ManifestError::Io)?;
+
+        if manifest.stages.is_empty() {
+            return Err(ManifestError::NoStage);
+        }
+        for (i, entry) in manifest.stages.iter().enumerate() {
+            if manifest.stages[..i].iter().any(|other| other.id == entry.id) {
+                return Err(ManifestError::DuplicateId(entry.id.clone()));
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// append the `.txt` stage files of the manifest directory which the manifest doesn't list,
+    /// sorted by file name, so new stages can be dropped in without editing it.
+    /// A file whose name is already the id of a stage is skipped rather than failing the load.
+    fn scan_directory(&mut self) -> std::io::Result<()> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "txt") {
+                files.push(path);
+            }
+        }
+        files.sort();
+
+        for path in files {
+            let file_name = PathBuf::from(path.file_name().unwrap());
+            let listed = self.stages.iter().any(|entry| match &entry.source {
+                StageSource::File(listed) => self.directory.join(listed) == path,
+                StageSource::Generated(_) => false,
+            });
+            if listed {
+                continue;
+            }
+            let id = file_name.to_string_lossy().into_owned();
+            if self.stages.iter().any(|entry| entry.id == id) {
+                log::warn!("skipped {}, its name is already the id of a stage", path.display());
+                continue;
+            }
+            let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+            self.stages.push(StageEntry {
+                id,
+                name,
+                chapter: EXTRA_CHAPTER.to_string(),
+                source: StageSource::File(file_name),
+                enemies: Vec::new(),
+                hunters: Vec::new(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// never true for a loaded manifest, which lists at least one stage
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    pub fn get(&self, stage: usize) -> Option<&StageEntry> {
+        self.stages.get(stage)
+    }
+
+    pub fn load_stage(&self, entry: &StageEntry) -> Result<Stage, StageLoadError> {
+        let stage = match &entry.source {
+            StageSource::File(path) => Stage::load(self.directory.join(path))?,
+            StageSource::Generated(config) => generate(config),
+        };
+        stage.with_patrols(&entry.enemies)
+            .map_err(StageLoadError::Patrol)?
+            .with_hunters(&entry.hunters)
+            .map_err(StageLoadError::Hunter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temporary_dir;
+
+    #[test]
+    fn stage_files_missing_from_the_manifest_are_appended() {
+        let dir = temporary_dir("manifest");
+        std::fs::write(
+            dir.join("manifest.ron"),
+            r#"(stages: [(id: "first", name: "First", chapter: "A", source: File("a.txt"))])"#,
+        ).unwrap();
+        for file in &["a.txt", "c.txt", "b.txt", "notes.md"] {
+            std::fs::write(dir.join(file), "@.G").unwrap();
+        }
+
+        let manifest = StageManifest::load(dir.join("manifest.ron")).unwrap();
+        let ids = manifest.stages.iter().map(|entry| entry.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["first", "b.txt", "c.txt"]);
+        assert_eq!(manifest.get(1).unwrap().chapter, EXTRA_CHAPTER);
+        assert!(manifest.load_stage(manifest.get(2).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn stage_files_named_like_a_listed_id_are_skipped() {
+        let dir = temporary_dir("manifest_collision");
+        std::fs::write(
+            dir.join("manifest.ron"),
+            r#"(stages: [(id: "b.txt", name: "First", chapter: "A", source: File("a.txt"))])"#,
+        ).unwrap();
+        for file in &["a.txt", "b.txt", "c.txt"] {
+            std::fs::write(dir.join(file), "@.G").unwrap();
+        }
+
+        let manifest = StageManifest::load(dir.join("manifest.ron")).unwrap();
+        let ids = manifest.stages.iter().map(|entry| entry.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["b.txt", "c.txt"]);
+        assert!(matches!(&manifest.get(0).unwrap().source, StageSource::File(path) if path == Path::new("a.txt")));
+    }
+}
@@ -1,7 +1,31 @@
+mod history;
+mod manifest;
+mod occupancy;
+mod previews;
+mod save;
+mod solutions;
+mod stats;
+mod targets;
+
+pub use history::{Change, MoveHistory};
+pub use manifest::StageManifest;
+pub use occupancy::Occupancy;
+pub use previews::StagePreviews;
+pub use save::SaveData;
+pub use solutions::Solutions;
+pub use stats::StageStats;
+pub use targets::StageTargets;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GameState {
     Play,
-    Win
+    /// `PauseState` is on top of `PlayState`
+    Pause,
+    Win,
+    /// an enemy caught the player
+    Lose,
+    /// the last stage of the manifest has been cleared
+    AllClear,
 }
 
 pub struct CurrentStageData {
@@ -19,8 +43,19 @@ impl Default for CurrentStageData {
 }
 
 impl CurrentStageData {
-    pub fn next_stage(&mut self) {
+    /// play the current stage again from the start
+    pub fn restart(&mut self) {
         self.state = GameState::Play;
-        self.stage += 1;
+    }
+
+    /// move on to the next stage of the manifest,
+    /// or to `GameState::AllClear` once the list is exhausted
+    pub fn next_stage(&mut self, manifest: &StageManifest) {
+        if self.stage + 1 < manifest.len() {
+            self.state = GameState::Play;
+            self.stage += 1;
+        } else {
+            self.state = GameState::AllClear;
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,144 @@
+//! Entities by the cell they stand on, for lookups which don't scan the storages.
+use std::ops::Deref;
+
+use amethyst::ecs::{
+    storage::{ComponentEvent, MaskedStorage},
+    Component, Entities, Entity, Join, ReaderId, Storage, WriteStorage,
+};
+
+use crate::components::grid2d::Grid2D;
+
+/// The entities with a `Grid2D` on every cell of the stage, in a dense array.
+/// It follows the `ComponentEvent`s of the `Grid2D` storage: systems `sync` it before a lookup.
+/// Entities outside of the stage aren't indexed, the rules treat the outside as blocked.
+pub struct Occupancy {
+    width: i32,
+    height: i32,
+    cells: Vec<Vec<Entity>>,
+    /// the cell of every indexed entity, by entity id
+    positions: Vec<Option<Grid2D>>,
+    reader: ReaderId<ComponentEvent>,
+}
+
+impl Occupancy {
+    /// index the entities of a stage `(height, width)` cells large,
+    /// the ones already on it and the ones created afterwards
+    pub fn new(entities: &Entities<'_>, grid2ds: &mut WriteStorage<'_, Grid2D>, (height, width): (i32, i32)) -> Self {
+        let mut occupancy = Self {
+            width,
+            height,
+            cells: vec![Vec::new(); (width * height) as usize],
+            positions: Vec::new(),
+            reader: grid2ds.register_reader(),
+        };
+        for (entity, grid2d) in (entities, &*grid2ds).join() {
+            occupancy.place(entity, grid2d);
+        }
+        occupancy
+    }
+
+    /// catch up with the `Grid2D`s inserted, modified and removed since the last call
+    pub fn sync<D>(&mut self, entities: &Entities<'_>, grid2ds: &Storage<'_, Grid2D, D>)
+    where
+        D: Deref<Target = MaskedStorage<Grid2D>>,
+    {
+        for event in grid2ds.channel().read(&mut self.reader) {
+            match *event {
+                ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) => {
+                    let entity = entities.entity(id);
+                    match grid2ds.get(entity) {
+                        Some(grid2d) => self.place(entity, grid2d),
+                        // removed again later in the same batch of events
+                        None => self.unplace(id),
+                    }
+                }
+                ComponentEvent::Removed(id) => self.unplace(id),
+            }
+        }
+    }
+
+    /// whether `cell` is part of the stage
+    pub fn contains(&self, cell: &Grid2D) -> bool {
+        (0..self.width).contains(&cell.x()) && (0..self.height).contains(&cell.y())
+    }
+
+    /// the entities on `cell`, none outside of the stage
+    pub fn at(&self, cell: &Grid2D) -> &[Entity] {
+        match self.index(cell) {
+            Some(index) => &self.cells[index],
+            None => &[],
+        }
+    }
+
+    /// the first entity on `cell` with a component of `storage`
+    pub fn find<'a, T, D>(&self, cell: &Grid2D, storage: &'a Storage<'_, T, D>) -> Option<(Entity, &'a T)>
+    where
+        T: Component,
+        D: Deref<Target = MaskedStorage<T>>,
+    {
+        self.at(cell).iter().find_map(|&entity| storage.get(entity).map(|component| (entity, component)))
+    }
+
+    fn index(&self, cell: &Grid2D) -> Option<usize> {
+        if self.contains(cell) {
+            Some((cell.y() * self.width + cell.x()) as usize)
+        } else {
+            None
+        }
+    }
+
+    fn place(&mut self, entity: Entity, cell: &Grid2D) {
+        let id = entity.id() as usize;
+        if self.positions.get(id).is_some_and(|position| position.as_ref() == Some(cell)) {
+            return;
+        }
+        self.unplace(entity.id());
+        if let Some(index) = self.index(cell) {
+            self.cells[index].push(entity);
+            if self.positions.len() <= id {
+                self.positions.resize(id + 1, None);
+            }
+            self.positions[id] = Some(cell.clone());
+        }
+    }
+
+    fn unplace(&mut self, id: u32) {
+        let position = self.positions.get_mut(id as usize).and_then(Option::take);
+        if let Some(index) = position.and_then(|cell| self.index(&cell)) {
+            self.cells[index].retain(|entity| entity.id() != id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::ecs::{Builder, World, WorldExt};
+
+    use super::*;
+
+    #[test]
+    fn follows_entities_from_cell_to_cell() {
+        let mut world = World::new();
+        world.register::<Grid2D>();
+        let wall = world.create_entity().with(Grid2D::new(1, 0)).build();
+        let mut occupancy = Occupancy::new(&world.entities(), &mut world.write_storage(), (2, 3));
+        let player = world.create_entity().with(Grid2D::new(0, 0)).build();
+        let outside = world.create_entity().with(Grid2D::new(3, 0)).build();
+
+        let sync = |occupancy: &mut Occupancy, world: &World| {
+            occupancy.sync(&world.entities(), &world.read_storage::<Grid2D>());
+        };
+        sync(&mut occupancy, &world);
+        assert_eq!(occupancy.at(&Grid2D::new(1, 0)), &[wall]);
+        assert_eq!(occupancy.at(&Grid2D::new(0, 0)), &[player]);
+        assert!(!occupancy.contains(&Grid2D::new(3, 0)));
+        assert_eq!(occupancy.at(&Grid2D::new(3, 0)), &[]);
+
+        *world.write_storage::<Grid2D>().get_mut(player).unwrap() = Grid2D::new(1, 0);
+        world.write_storage::<Grid2D>().remove(wall);
+        world.delete_entity(outside).unwrap();
+        sync(&mut occupancy, &world);
+        assert_eq!(occupancy.at(&Grid2D::new(1, 0)), &[player]);
+        assert_eq!(occupancy.at(&Grid2D::new(0, 0)), &[]);
+    }
+}
@@ -0,0 +1,20 @@
+use std::collections::HashMap;
+
+use super::manifest::{StageEntry, StageManifest};
+
+/// Size of each stage the stage select has shown, by stage id,
+/// so that opening it again doesn't load or generate every stage anew.
+#[derive(Debug, Default)]
+pub struct StagePreviews {
+    sizes: HashMap<String, Option<(i32, i32)>>,
+}
+
+impl StagePreviews {
+    /// `(height, width)` of the stage of `entry`, loaded the first time.
+    /// `None` if the stage could not be loaded.
+    pub fn size(&mut self, manifest: &StageManifest, entry: &StageEntry) -> Option<(i32, i32)> {
+        *self.sizes.entry(entry.id.clone()).or_insert_with(|| {
+            manifest.load_stage(entry).ok().map(|stage| stage.size())
+        })
+    }
+}
@@ -0,0 +1,295 @@
+//! Progress, records and settings kept between runs, stored as RON in the user's data directory.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsString,
+    fmt,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::resources::StageManifest;
+
+/// Overrides the directory of the save file, mainly for tests.
+pub const DATA_DIR_VAR: &str = "MAZE_GAME_DATA_DIR";
+
+/// Version written in new save files.
+/// Bump it when a field changes meaning or is removed, and add a step from the previous layout
+/// to `migrate`, like `v0_to_v1`.
+/// Added fields only need a default.
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StageRecord {
+    /// seconds
+    pub best_time: Option<f32>,
+    pub best_moves: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub show_hud: bool,
+    /// tween the player between cells instead of jumping
+    pub animations: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            show_hud: true,
+            animations: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub version: u32,
+    /// ids of the stages unlocked besides the first one
+    pub unlocked: BTreeSet<String>,
+    /// records by stage id
+    pub records: BTreeMap<String, StageRecord>,
+    pub settings: Settings,
+    /// where `store` writes, `None` if there is no data directory
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            unlocked: BTreeSet::new(),
+            records: BTreeMap::new(),
+            settings: Settings::default(),
+            path: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Ron(ron::de::Error),
+    Serialize(ron::ser::Error),
+    /// written by a newer version of the game
+    UnknownVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{}", e),
+            SaveError::Ron(e) => write!(f, "invalid save file: {}", e),
+            SaveError::Serialize(e) => write!(f, "failed to serialize save data: {}", e),
+            SaveError::UnknownVersion(v) => write!(f, "save file version {} is newer than {}", v, SAVE_VERSION),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+/// `save.ron` in `MAZE_GAME_DATA_DIR`, or in the user's data directory
+pub fn save_path() -> Option<PathBuf> {
+    save_path_in(std::env::var_os(DATA_DIR_VAR))
+}
+
+/// `save.ron` in `data_dir`, or in the user's data directory without one
+fn save_path_in(data_dir: Option<OsString>) -> Option<PathBuf> {
+    let dir = match data_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_dir()?.join("maze_game"),
+    };
+    Some(dir.join("save.ron"))
+}
+
+#[derive(Deserialize)]
+struct Version {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Read any known version of the save file into the current layout,
+/// converting it one version after the other.
+fn migrate(source: &str) -> Result<SaveData, SaveError> {
+    let Version { version } = ron::de::from_str(source).map_err(SaveError::Ron)?;
+    let parse = || ron::de::from_str(source).map_err(SaveError::Ron);
+    match version {
+        0 => Ok(v0_to_v1(parse()?)),
+        1 => parse(),
+        v => Err(SaveError::UnknownVersion(v)),
+    }
+}
+
+/// Files without `version` were written before it was added, with the fields of version 1.
+fn v0_to_v1(data: SaveData) -> SaveData {
+    SaveData { version: 1, ..data }
+}
+
+impl SaveData {
+    /// Load the save file of `save_path`, see `recover_from`.
+    pub fn load() -> Self {
+        match save_path() {
+            Some(path) => Self::recover_from(path),
+            None => {
+                log::warn!("no data directory, progress will not be saved");
+                Self::default()
+            }
+        }
+    }
+
+    /// Load a save file, falling back to default data:
+    /// - a corrupt file is kept aside as `save.ron.bak` and replaced by the next `store`,
+    /// - a file which couldn't be read or was written by a newer version is left untouched,
+    ///   the progress of this run isn't stored over it.
+    pub fn recover_from<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        match Self::load_from(path) {
+            Ok(data) => data,
+            Err(e @ SaveError::Ron(_)) => {
+                log::warn!("failed to load {}: {}", path.display(), e);
+                if let Err(e) = fs::rename(path, path.with_extension("ron.bak")) {
+                    log::warn!("failed to back up {}: {}", path.display(), e);
+                }
+                Self { path: Some(path.to_path_buf()), ..Self::default() }
+            }
+            Err(e) => {
+                log::warn!("failed to load {}: {}, progress will not be saved", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Load a save file, a missing file gives default data which will be stored at `path`.
+    pub fn load_from<P: AsRef<Path>>(path: P) -> Result<Self, SaveError> {
+        let path = path.as_ref();
+        let mut data = match fs::read_to_string(path) {
+            Ok(source) => migrate(&source)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => return Err(SaveError::Io(e)),
+        };
+        data.path = Some(path.to_path_buf());
+        Ok(data)
+    }
+
+    /// Write the data where it was loaded from. The file is replaced atomically,
+    /// so a crash while saving leaves the previous save intact.
+    pub fn store(&self) -> Result<(), SaveError> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(SaveError::Io)?;
+        }
+        let text = ron::ser::to_string_pretty(self, Default::default()).map_err(SaveError::Serialize)?;
+
+        let temporary = path.with_extension("ron.tmp");
+        let mut file = fs::File::create(&temporary).map_err(SaveError::Io)?;
+        file.write_all(text.as_bytes()).map_err(SaveError::Io)?;
+        file.sync_all().map_err(SaveError::Io)?;
+        fs::rename(&temporary, path).map_err(SaveError::Io)
+    }
+
+    /// the first stage is always unlocked
+    pub fn is_unlocked(&self, manifest: &StageManifest, stage: usize) -> bool {
+        stage == 0 || manifest.get(stage).is_some_and(|entry| self.unlocked.contains(&entry.id))
+    }
+
+    /// the first unlocked stage not cleared yet, or the last unlocked one
+    pub fn resume_stage(&self, manifest: &StageManifest) -> usize {
+        let unlocked = (0..manifest.len()).filter(|&stage| self.is_unlocked(manifest, stage));
+        let mut last = 0;
+        for stage in unlocked {
+            let id = &manifest.get(stage).unwrap().id;
+            if !self.records.contains_key(id) {
+                return stage;
+            }
+            last = stage;
+        }
+        last
+    }
+
+    /// keep the best time and move count of a clear of `stage` and unlock the next one
+    pub fn record_clear(&mut self, manifest: &StageManifest, stage: usize, moves: u32, time: f32) {
+        if let Some(entry) = manifest.get(stage) {
+            let record = self.records.entry(entry.id.clone()).or_default();
+            record.best_moves = Some(record.best_moves.map_or(moves, |best| best.min(moves)));
+            record.best_time = Some(record.best_time.map_or(time, |best| best.min(time)));
+        }
+        if let Some(next) = manifest.get(stage + 1) {
+            self.unlocked.insert(next.id.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temporary_dir;
+
+    #[test]
+    fn stores_and_loads_records() {
+        let path = temporary_dir("roundtrip").join("save.ron");
+        let manifest = StageManifest::load("resources/stages/manifest.ron").unwrap();
+
+        let mut data = SaveData::load_from(&path).unwrap();
+        assert_eq!(data.resume_stage(&manifest), 0);
+        data.record_clear(&manifest, 0, 30, 12.5);
+        data.record_clear(&manifest, 0, 25, 20.);
+        data.settings.animations = false;
+        data.store().unwrap();
+
+        let loaded = SaveData::load_from(&path).unwrap();
+        assert_eq!(loaded, data);
+        let record = &loaded.records[&manifest.get(0).unwrap().id];
+        assert_eq!((record.best_moves, record.best_time), (Some(25), Some(12.5)));
+        assert!(loaded.is_unlocked(&manifest, 1));
+        assert_eq!(loaded.resume_stage(&manifest), 1);
+    }
+
+    #[test]
+    fn reads_unversioned_and_rejects_newer_files() {
+        let data = migrate("(unlocked: [\"small-room\"])").unwrap();
+        assert_eq!(data.version, SAVE_VERSION);
+        assert!(data.unlocked.contains("small-room"));
+
+        match migrate(&format!("(version: {})", SAVE_VERSION + 1)) {
+            Err(SaveError::UnknownVersion(v)) => assert_eq!(v, SAVE_VERSION + 1),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn corrupt_files_are_backed_up_and_newer_ones_left_alone() {
+        let dir = temporary_dir("recover");
+
+        let corrupt = dir.join("corrupt.ron");
+        fs::write(&corrupt, "(unlocked: [").unwrap();
+        let data = SaveData::recover_from(&corrupt);
+        assert_eq!(data.path, Some(corrupt.clone()));
+        assert_eq!(fs::read_to_string(corrupt.with_extension("ron.bak")).unwrap(), "(unlocked: [");
+        assert!(!corrupt.exists());
+
+        let newer = dir.join("newer.ron");
+        let source = format!("(version: {}, unlocked: [\"small-room\"])", SAVE_VERSION + 1);
+        fs::write(&newer, &source).unwrap();
+        let data = SaveData::recover_from(&newer);
+        assert_eq!(data.path, None);
+        data.store().unwrap();
+        assert_eq!(fs::read_to_string(&newer).unwrap(), source);
+        assert!(!newer.with_extension("ron.bak").exists());
+    }
+
+    #[test]
+    fn data_dir_can_be_overridden() {
+        let dir = PathBuf::from("overridden");
+        assert_eq!(save_path_in(Some(dir.clone().into())), Some(dir.join("save.ron")));
+        assert_eq!(save_path_in(None), dirs::data_dir().map(|dir| dir.join("maze_game").join("save.ron")));
+    }
+}
@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::SOLVER_BUDGET,
+    stage::{solve_within, Stage},
+};
+
+/// Length of the shortest solution of each stage started so far, by stage id,
+/// so that restarting a stage doesn't search it again.
+#[derive(Debug, Default)]
+pub struct Solutions {
+    optimal_moves: HashMap<String, Option<u32>>,
+}
+
+impl Solutions {
+    /// length of the shortest solution of the stage `id`, searched within `SOLVER_BUDGET`
+    /// the first time. `None` if the solver gave up or found that there is none.
+    pub fn optimal_moves(&mut self, id: &str, stage: &Stage) -> Option<u32> {
+        *self.optimal_moves.entry(id.to_string()).or_insert_with(|| {
+            match solve_within(stage, SOLVER_BUDGET) {
+                Some(Ok(solution)) => Some(solution.len() as u32),
+                Some(Err(unsolvable)) => {
+                    log::warn!("stage {:?} has no solution: {}", id, unsolvable);
+                    None
+                }
+                None => {
+                    log::warn!("gave up searching the shortest solution of stage {:?}", id);
+                    None
+                }
+            }
+        })
+    }
+}
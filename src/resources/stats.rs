@@ -0,0 +1,33 @@
+/// Counters of the stage being played, reset whenever a stage starts.
+#[derive(Clone, Debug, Default)]
+pub struct StageStats {
+    /// successful moves, undone moves are taken back
+    pub moves: u32,
+    /// moves refused by an obstacle
+    pub bumps: u32,
+    /// seconds spent in `GameState::Play`
+    pub elapsed: f32,
+    /// length of the shortest solution, `None` until the stage is loaded
+    /// or if the solver gave up on it
+    pub optimal_moves: Option<u32>,
+}
+
+impl StageStats {
+    /// elapsed time as `m:ss.ss`
+    pub fn elapsed_text(&self) -> String {
+        let minutes = (self.elapsed / 60.) as u32;
+        format!("{}:{:05.2}", minutes, self.elapsed - minutes as f32 * 60.)
+    }
+
+    /// 3 stars for a shortest solution, 2 for at most half again as many moves, 1 otherwise
+    pub fn stars(&self) -> Option<u32> {
+        let optimal = self.optimal_moves?;
+        Some(if self.moves <= optimal {
+            3
+        } else if self.moves * 2 <= optimal * 3 {
+            2
+        } else {
+            1
+        })
+    }
+}
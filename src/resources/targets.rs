@@ -0,0 +1,9 @@
+use crate::components::grid2d::Grid2D;
+
+/// Cells of the goals and pads of the stage being played. They never move,
+/// so `WinSystem` only looks them up once the stage is spawned.
+#[derive(Clone, Debug, Default)]
+pub struct StageTargets {
+    pub goals: Vec<Grid2D>,
+    pub pads: Vec<Grid2D>,
+}
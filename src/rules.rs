@@ -0,0 +1,214 @@
+//! Rules of a turn: how the player moves, when enemies catch it and when a stage is cleared.
+//! They only see the stage through `Board`, so the systems, the solver and the tools
+//! play by the same rules whatever holds the stage.
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    grid::{Direction, Position},
+    stage::chase_step,
+};
+
+/// What the rules need to know about the cells of a stage at the start of a turn.
+pub trait Board<P> {
+    /// walls, doors whose key the player doesn't hold and the outside of the stage
+    fn is_blocked(&self, cell: &P) -> bool;
+    fn has_box(&self, cell: &P) -> bool;
+    /// a key lies on `cell`, waiting to be collected
+    fn has_key(&self, cell: &P) -> bool;
+    fn is_ice(&self, cell: &P) -> bool;
+    /// whether `cell` may be entered or left by moving towards `direction`, see `Tile::allows`
+    fn allows(&self, cell: &P, direction: Direction) -> bool;
+    /// the other teleporter with the same digit as the one on `cell`
+    fn teleporter_partner(&self, cell: &P) -> Option<P>;
+}
+
+/// `Board` made of sets of cells, filled from whatever holds the stage.
+#[derive(Clone, Debug)]
+pub struct CellBoard<P: Position> {
+    pub blocked: HashSet<P>,
+    pub boxes: HashSet<P>,
+    pub keys: HashSet<P>,
+    pub ice: HashSet<P>,
+    pub arrows: HashMap<P, Direction>,
+    pub teleporters: HashMap<P, P>,
+}
+
+impl<P: Position> Default for CellBoard<P> {
+    fn default() -> Self {
+        Self {
+            blocked: HashSet::new(),
+            boxes: HashSet::new(),
+            keys: HashSet::new(),
+            ice: HashSet::new(),
+            arrows: HashMap::new(),
+            teleporters: HashMap::new(),
+        }
+    }
+}
+
+impl<P: Position> Board<P> for CellBoard<P> {
+    fn is_blocked(&self, cell: &P) -> bool {
+        self.blocked.contains(cell)
+    }
+
+    fn has_box(&self, cell: &P) -> bool {
+        self.boxes.contains(cell)
+    }
+
+    fn has_key(&self, cell: &P) -> bool {
+        self.keys.contains(cell)
+    }
+
+    fn is_ice(&self, cell: &P) -> bool {
+        self.ice.contains(cell)
+    }
+
+    fn allows(&self, cell: &P, direction: Direction) -> bool {
+        self.arrows.get(cell).is_none_or(|&arrow| arrow == direction)
+    }
+
+    fn teleporter_partner(&self, cell: &P) -> Option<P> {
+        self.teleporters.get(cell).cloned()
+    }
+}
+
+/// A move of the player allowed by `plan_move`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Move<P> {
+    /// cells the player walks over, more than one when it slides over ice.
+    /// The player collects the keys on every one of them.
+    pub path: Vec<P>,
+    /// the box on the first cell of `path`, pushed from the first cell to the second one
+    pub pushed: Option<(P, P)>,
+    /// the partner of the teleporter at the end of `path`, where the player ends
+    pub teleport: Option<P>,
+}
+
+impl<P> Move<P> {
+    /// the cell the player ends on
+    pub fn end(&self) -> &P {
+        self.teleport.as_ref().unwrap_or_else(|| self.path.last().unwrap())
+    }
+}
+
+/// The move of the player from `from` towards `direction`, `None` if it bumps:
+/// - blocked cells and arrows pointing elsewhere can't be entered or left,
+/// - a box moves on if the cell beyond holds no obstacle, box or key,
+/// - on ice the player keeps sliding until the cell ahead is blocked or it leaves the ice.
+///   A pushed box stays right ahead, so pushing never slides,
+/// - the player ends on the partner of a teleporter, unless a box is there.
+pub fn plan_move<P: Position>(board: &impl Board<P>, from: &P, direction: Direction) -> Option<Move<P>> {
+    let next = from.neighbor(direction);
+    if board.is_blocked(&next) || !board.allows(from, direction) || !board.allows(&next, direction) {
+        return None;
+    }
+
+    let pushed = if board.has_box(&next) {
+        let beyond = next.neighbor(direction);
+        if board.is_blocked(&beyond) || board.has_box(&beyond) || board.has_key(&beyond) {
+            return None;
+        }
+        Some((next.clone(), beyond))
+    } else {
+        None
+    };
+    let has_box = |cell: &P| match &pushed {
+        Some((_, to)) if cell == to => true,
+        Some((from, _)) if cell == from => false,
+        _ => board.has_box(cell),
+    };
+
+    let mut path = vec![next];
+    while pushed.is_none() && board.is_ice(path.last().unwrap()) {
+        let ahead = path.last().unwrap().neighbor(direction);
+        if board.is_blocked(&ahead) || !board.allows(&ahead, direction) || board.has_box(&ahead) {
+            break;
+        }
+        path.push(ahead);
+    }
+
+    let teleport = board.teleporter_partner(path.last().unwrap()).filter(|partner| !has_box(partner));
+    Some(Move { path, pushed, teleport })
+}
+
+/// where a hunter at `from` ends after walking up to `speed` cells towards `player`,
+/// around blocked cells and boxes, see `chase_step`. It stops on the player.
+pub fn hunt<P: Position>(board: &impl Board<P>, from: &P, player: &P, speed: u32, vision: u32) -> P {
+    let neighbors = |cell: &P| {
+        Direction::ALL.iter()
+            .map(|&direction| cell.neighbor(direction))
+            .filter(|next| !board.is_blocked(next) && !board.has_box(next))
+            .collect::<Vec<_>>()
+    };
+    let mut position = from.clone();
+    for _ in 0..speed {
+        match chase_step(&position, player, vision, neighbors) {
+            Some(next) => position = next,
+            None => break,
+        }
+    }
+    position
+}
+
+/// An enemy catches the player which ends its move on the cell the enemy leaves,
+/// crossing it, or on the cell the enemy ends on.
+pub fn is_caught<P: PartialEq>(player: &P, enemy_before: &P, enemy_after: &P) -> bool {
+    player == enemy_before || player == enemy_after
+}
+
+/// The stage is cleared when the player stands on a goal with a box on every pad.
+pub fn is_cleared<P: PartialEq>(board: &impl Board<P>, player: &P, goals: &[P], pads: &[P]) -> bool {
+    goals.contains(player) && pads.iter().all(|pad| board.has_box(pad))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a corridor of `length` cells along `x`, on line 0
+    fn corridor(length: i32) -> CellBoard<(i32, i32)> {
+        let mut board = CellBoard::default();
+        for x in 0..length {
+            board.blocked.insert((1, x));
+            board.blocked.insert((-1, x));
+        }
+        board.blocked.insert((0, -1));
+        board.blocked.insert((0, length));
+        board
+    }
+
+    #[test]
+    fn bumps_into_walls_and_stuck_boxes() {
+        let mut board = corridor(3);
+        assert_eq!(plan_move(&board, &(0, 0), Direction::Left), None);
+        assert_eq!(plan_move(&board, &(0, 0), Direction::Up), None);
+
+        board.boxes.insert((0, 1));
+        let push = plan_move(&board, &(0, 0), Direction::Right).unwrap();
+        assert_eq!(push.pushed, Some(((0, 1), (0, 2))));
+        assert_eq!(push.end(), &(0, 1));
+        board.keys.insert((0, 2));
+        assert_eq!(plan_move(&board, &(0, 0), Direction::Right), None);
+    }
+
+    #[test]
+    fn slides_then_teleports() {
+        let mut board = corridor(5);
+        board.ice.extend(vec![(0, 1), (0, 2)]);
+        let slide = plan_move(&board, &(0, 0), Direction::Right).unwrap();
+        assert_eq!(slide.path, vec![(0, 1), (0, 2), (0, 3)]);
+
+        board.teleporters.insert((0, 3), (5, 5));
+        assert_eq!(plan_move(&board, &(0, 0), Direction::Right).unwrap().end(), &(5, 5));
+        board.boxes.insert((5, 5));
+        assert_eq!(plan_move(&board, &(0, 0), Direction::Right).unwrap().end(), &(0, 3));
+    }
+
+    #[test]
+    fn hunters_stop_on_the_player() {
+        let board = corridor(6);
+        assert_eq!(hunt(&board, &(0, 5), &(0, 0), 2, u32::MAX), (0, 3));
+        assert_eq!(hunt(&board, &(0, 2), &(0, 0), 3, u32::MAX), (0, 0));
+        assert_eq!(hunt(&board, &(0, 5), &(0, 0), 2, 4), (0, 5));
+    }
+}
@@ -0,0 +1,130 @@
+//! Enemies chasing the player, given next to a stage in the manifest.
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    hash::Hash,
+};
+
+use serde::Deserialize;
+
+use super::Stage;
+
+fn default_speed() -> u32 {
+    1
+}
+
+fn default_vision() -> u32 {
+    u32::MAX
+}
+
+/// An enemy which steps towards the player after each player move, see `chase_step`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Hunter {
+    /// `(y, x)` stage position the hunter starts on
+    pub start: (i32, i32),
+    /// cells walked after each player move
+    #[serde(default = "default_speed")]
+    pub speed: u32,
+    /// the hunter waits until the player is at most this many steps away, unlimited by default
+    #[serde(default = "default_vision")]
+    pub vision: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HunterErrorKind {
+    /// the start is a wall or outside of the stage
+    Blocked((i32, i32)),
+    NoSpeed,
+}
+
+/// Error about the hunter at index `hunter` in the manifest entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HunterError {
+    pub hunter: usize,
+    pub kind: HunterErrorKind,
+}
+
+impl fmt::Display for HunterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hunter {}: ", self.hunter + 1)?;
+        match self.kind {
+            HunterErrorKind::Blocked((y, x)) => write!(f, "starts in a wall at line {}, column {}", y + 1, x + 1),
+            HunterErrorKind::NoSpeed => write!(f, "speed must be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for HunterError {}
+
+impl Hunter {
+    pub fn check(&self, stage: &Stage) -> Result<(), HunterErrorKind> {
+        if self.speed == 0 {
+            return Err(HunterErrorKind::NoSpeed);
+        }
+        match stage.tile(self.start) {
+            Some(tile) if !tile.is_obstacle() => Ok(()),
+            _ => Err(HunterErrorKind::Blocked(self.start)),
+        }
+    }
+}
+
+/// The cell a hunter at `from` steps on to get closer to `target`, following one of
+/// the shortest paths through the cells given by `neighbors`. Neighbors are tried
+/// in the order they are given, so that the game and the solver pick the same path.
+/// `None` if the hunter is on `target`, or if `target` is more than `vision` steps away.
+pub fn chase_step<P, N>(from: &P, target: &P, vision: u32, neighbors: N) -> Option<P>
+where
+    P: Clone + Eq + Hash,
+    N: Fn(&P) -> Vec<P>,
+{
+    // the first step of the path which first reached each cell
+    let mut first_steps: HashMap<P, P> = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((from.clone(), 0));
+    while let Some((cell, distance)) = queue.pop_front() {
+        if distance == vision {
+            continue;
+        }
+        for next in neighbors(&cell) {
+            if next == *from || first_steps.contains_key(&next) {
+                continue;
+            }
+            let first_step = if cell == *from { next.clone() } else { first_steps[&cell].clone() };
+            if next == *target {
+                return Some(first_step);
+            }
+            first_steps.insert(next.clone(), first_step);
+            queue.push_back((next, distance + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Direction;
+
+    fn open_neighbors(stage: &Stage) -> impl Fn(&(i32, i32)) -> Vec<(i32, i32)> + '_ {
+        move |&(y, x)| {
+            Direction::ALL.iter()
+                .map(|direction| direction.offset())
+                .map(|(dy, dx)| (y + dy, x + dx))
+                .filter(|&position| stage.tile(position).is_some_and(|tile| !tile.is_obstacle()))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn steps_along_a_shortest_path_within_vision() {
+        let stage: Stage = "@.#..\n..#.#\n....G".parse().unwrap();
+        let neighbors = open_neighbors(&stage);
+        // around the wall, through the bottom line
+        assert_eq!(chase_step(&(0, 3), &(0, 0), u32::MAX, &neighbors), Some((1, 3)));
+        assert_eq!(chase_step(&(1, 3), &(0, 0), u32::MAX, &neighbors), Some((2, 3)));
+        // 7 steps away
+        assert_eq!(chase_step(&(0, 3), &(0, 0), 6, &neighbors), None);
+        assert_eq!(chase_step(&(0, 3), &(0, 0), 7, &neighbors), Some((1, 3)));
+        assert_eq!(chase_step(&(0, 0), &(0, 0), u32::MAX, &neighbors), None);
+    }
+}
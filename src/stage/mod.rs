@@ -0,0 +1,248 @@
+//! Stage model shared by the loader, the states and the systems.
+use std::{fmt, path::Path};
+
+use crate::grid::Direction;
+
+mod hunter;
+mod parser;
+mod patrol;
+mod solver;
+
+pub use hunter::{chase_step, Hunter, HunterError};
+pub use parser::{parse_stage, StageParseError};
+pub use patrol::{Patrol, PatrolError};
+pub use solver::{solve, solve_within, Unsolvable};
+
+/// A single cell of a stage file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Wall,
+    Player,
+    Goal,
+    /// `a`-`z` except `v`, which is the down arrow, collected by walking on it
+    Key(char),
+    /// `A`-`Z` except `G`, which is the goal, and `V`, which no key could open since `v`
+    /// is the down arrow; a `V` in a stage file is an unknown tile.
+    /// Blocks the player until the key with the same letter is collected.
+    /// The letter is stored in lowercase, like the key's.
+    Door(char),
+    /// `$`, pushed one cell by walking into it
+    Box,
+    /// `+`, every pad must hold a box before the goal counts
+    Pad,
+    /// `*`, a box starting on a pad
+    BoxOnPad,
+    /// `~`, the player slides over it until blocked
+    Ice,
+    /// `1`-`9`, each digit appears on exactly two tiles which send the player to each other
+    Teleporter(char),
+    /// `^`, `v`, `<` and `>`, entered and left only by moving in that direction on screen.
+    /// The first line of a stage file is drawn at the bottom, so `^` points to the next line.
+    Arrow(Direction),
+}
+
+impl Tile {
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '.' => Some(Tile::Empty),
+            '#' => Some(Tile::Wall),
+            '@' => Some(Tile::Player),
+            'G' => Some(Tile::Goal),
+            '$' => Some(Tile::Box),
+            '+' => Some(Tile::Pad),
+            '*' => Some(Tile::BoxOnPad),
+            '~' => Some(Tile::Ice),
+            '^' => Some(Tile::Arrow(Direction::Up)),
+            'v' => Some(Tile::Arrow(Direction::Down)),
+            '<' => Some(Tile::Arrow(Direction::Left)),
+            '>' => Some(Tile::Arrow(Direction::Right)),
+            '1'..='9' => Some(Tile::Teleporter(c)),
+            'a'..='z' => Some(Tile::Key(c)),
+            // `v` is the down arrow, so no key opens a door `V`
+            'V' => None,
+            'A'..='Z' => Some(Tile::Door(c.to_ascii_lowercase())),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Empty => '.',
+            Tile::Wall => '#',
+            Tile::Player => '@',
+            Tile::Goal => 'G',
+            Tile::Box => '$',
+            Tile::Pad => '+',
+            Tile::BoxOnPad => '*',
+            Tile::Ice => '~',
+            Tile::Teleporter(digit) => digit,
+            Tile::Arrow(Direction::Up) => '^',
+            Tile::Arrow(Direction::Down) => 'v',
+            Tile::Arrow(Direction::Left) => '<',
+            Tile::Arrow(Direction::Right) => '>',
+            Tile::Key(letter) => letter,
+            Tile::Door(letter) => letter.to_ascii_uppercase(),
+        }
+    }
+
+    /// whether the tile always blocks the player, like an entity with `Obstacle`.
+    /// Doors only block the player until their key is collected.
+    pub fn is_obstacle(self) -> bool {
+        self == Tile::Wall
+    }
+
+    pub fn has_box(self) -> bool {
+        matches!(self, Tile::Box | Tile::BoxOnPad)
+    }
+
+    pub fn is_pad(self) -> bool {
+        matches!(self, Tile::Pad | Tile::BoxOnPad)
+    }
+
+    /// whether the player may enter or leave this tile by moving towards `direction`
+    pub fn allows(self, direction: Direction) -> bool {
+        match self {
+            Tile::Arrow(arrow) => arrow == direction,
+            _ => true,
+        }
+    }
+}
+
+/// A validated stage: rectangular, exactly one player and at least one goal.
+/// Positions are `(y, x)` pairs where `y` is the line index in the stage file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stage {
+    height: i32,
+    width: i32,
+    tiles: Vec<Vec<Tile>>,
+    player: (i32, i32),
+    /// cells of each enemy turn after turn, see `Patrol::cycle`
+    patrols: Vec<Vec<(i32, i32)>>,
+    hunters: Vec<Hunter>,
+}
+
+impl Stage {
+    /// read and parse a stage file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, StageLoadError> {
+        let source = std::fs::read_to_string(path).map_err(StageLoadError::Io)?;
+        source.parse().map_err(StageLoadError::Parse)
+    }
+
+    /// `(height, width)` of the stage
+    pub fn size(&self) -> (i32, i32) {
+        (self.height, self.width)
+    }
+
+    pub fn player(&self) -> (i32, i32) {
+        self.player
+    }
+
+    /// tile at `(y, x)`, `None` outside of the stage
+    pub fn tile(&self, (y, x): (i32, i32)) -> Option<Tile> {
+        if y < 0 || x < 0 {
+            return None;
+        }
+        self.tiles.get(y as usize)?.get(x as usize).copied()
+    }
+
+    /// place the enemies of the manifest on the stage
+    pub fn with_patrols(mut self, patrols: &[Patrol]) -> Result<Self, PatrolError> {
+        self.patrols = patrols.iter()
+            .enumerate()
+            .map(|(enemy, patrol)| patrol.cycle(&self).map_err(|kind| PatrolError { enemy, kind }))
+            .collect::<Result<_, _>>()?;
+        Ok(self)
+    }
+
+    pub fn patrols(&self) -> &[Vec<(i32, i32)>] {
+        &self.patrols
+    }
+
+    /// place the hunters of the manifest on the stage
+    pub fn with_hunters(mut self, hunters: &[Hunter]) -> Result<Self, HunterError> {
+        for (hunter, config) in hunters.iter().enumerate() {
+            config.check(&self).map_err(|kind| HunterError { hunter, kind })?;
+        }
+        self.hunters = hunters.to_vec();
+        Ok(self)
+    }
+
+    pub fn hunters(&self) -> &[Hunter] {
+        &self.hunters
+    }
+
+    /// build a stage from rows of tiles, checked like a parsed file
+    pub fn from_tiles(tiles: Vec<Vec<Tile>>) -> Result<Self, StageParseError> {
+        parser::build_stage(tiles.into_iter().enumerate().map(|(i, row)| (i + 1, row)).collect())
+    }
+
+    /// the other teleporter with the same digit as the one at `position`
+    pub fn teleporter_partner(&self, position: (i32, i32)) -> Option<(i32, i32)> {
+        let digit = match self.tile(position)? {
+            Tile::Teleporter(digit) => digit,
+            _ => return None,
+        };
+        self.cells()
+            .find(|&(other, tile)| tile == Tile::Teleporter(digit) && other != position)
+            .map(|(other, _)| other)
+    }
+
+    /// every cell of the stage with its position, row by row
+    pub fn cells(&self) -> impl Iterator<Item = ((i32, i32), Tile)> + '_ {
+        self.tiles.iter().enumerate().flat_map(|(i, row)| {
+            row.iter().enumerate().map(move |(j, &tile)| ((i as i32, j as i32), tile))
+        })
+    }
+
+    /// positions of the cells whose tile is `wanted`, row by row
+    pub fn positions_of(&self, wanted: impl Fn(Tile) -> bool) -> Vec<(i32, i32)> {
+        self.cells().filter(|&(_, tile)| wanted(tile)).map(|(position, _)| position).collect()
+    }
+}
+
+impl std::str::FromStr for Stage {
+    type Err = StageParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_stage(s)
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.tiles.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for tile in row {
+                write!(f, "{}", tile.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum StageLoadError {
+    /// index of a stage past the end of the manifest, from an edited manifest or an old save
+    NotInManifest(usize),
+    Io(std::io::Error),
+    Parse(StageParseError),
+    Patrol(PatrolError),
+    Hunter(HunterError),
+}
+
+impl fmt::Display for StageLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageLoadError::NotInManifest(stage) => write!(f, "the manifest has no stage {}", stage + 1),
+            StageLoadError::Io(e) => write!(f, "{}", e),
+            StageLoadError::Parse(e) => write!(f, "{}", e),
+            StageLoadError::Patrol(e) => write!(f, "{}", e),
+            StageLoadError::Hunter(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for StageLoadError {}
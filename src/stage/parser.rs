@@ -0,0 +1,258 @@
+//! Parse the text format of `resources/stages/*.txt` into a `Stage`.
+use std::fmt;
+
+use super::{Stage, Tile};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StageParseErrorKind {
+    /// the file contains no tile at all
+    EmptyFile,
+    UnknownTile(char),
+    /// a row whose length differs from the first row
+    RaggedRow { expected: usize, found: usize },
+    MissingPlayer,
+    /// a second `@`; holds the 1-based `(line, column)` of the first one
+    DuplicatePlayer { first: (usize, usize) },
+    MissingGoal,
+    /// more pads `+` than boxes `$` to cover them
+    TooFewBoxes { boxes: usize, pads: usize },
+    /// a teleporter digit which does not appear exactly twice;
+    /// the error points at its first occurrence
+    UnpairedTeleporter { digit: char, count: usize },
+}
+
+/// Error returned by `parse_stage`.
+/// `line` and `column` are 1-based and `None` for errors about the whole file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StageParseError {
+    pub kind: StageParseErrorKind,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl StageParseError {
+    fn at(kind: StageParseErrorKind, line: usize, column: usize) -> Self {
+        Self { kind, line: Some(line), column: Some(column) }
+    }
+
+    fn whole_file(kind: StageParseErrorKind) -> Self {
+        Self { kind, line: None, column: None }
+    }
+}
+
+impl fmt::Display for StageParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageParseErrorKind::EmptyFile => write!(f, "stage file is empty"),
+            StageParseErrorKind::UnknownTile('V') => write!(
+                f, "unknown tile 'V', no door since 'v' is the down arrow and no key"
+            ),
+            StageParseErrorKind::UnknownTile(c) => write!(f, "unknown tile {:?}", c),
+            StageParseErrorKind::RaggedRow { expected, found } => write!(
+                f, "row has {} tiles but the first row has {}", found, expected
+            ),
+            StageParseErrorKind::MissingPlayer => write!(f, "no player '@' in the stage"),
+            StageParseErrorKind::DuplicatePlayer { first: (line, column) } => write!(
+                f, "second player '@' (first one at line {}, column {})", line, column
+            ),
+            StageParseErrorKind::MissingGoal => write!(f, "no goal 'G' in the stage"),
+            StageParseErrorKind::TooFewBoxes { boxes, pads } => write!(
+                f, "{} pads '+' but only {} boxes '$' to cover them", pads, boxes
+            ),
+            StageParseErrorKind::UnpairedTeleporter { digit, count } => write!(
+                f, "teleporter {:?} appears {} times instead of twice", digit, count
+            ),
+        }
+    }
+}
+
+impl fmt::Display for StageParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: {}", line, column, self.kind),
+            (Some(line), None) => write!(f, "line {}: {}", line, self.kind),
+            _ => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for StageParseError {}
+
+/// Parse a stage file.
+/// Blank lines before and after the map are ignored, but still counted in line numbers.
+pub fn parse_stage(source: &str) -> Result<Stage, StageParseError> {
+    let lines = source
+        .lines()
+        .map(|line| line.trim_end())
+        .enumerate()
+        .skip_while(|(_, line)| line.is_empty())
+        .collect::<Vec<_>>();
+    let last = match lines.iter().rposition(|(_, line)| !line.is_empty()) {
+        Some(last) => last,
+        None => return Err(StageParseError::whole_file(StageParseErrorKind::EmptyFile)),
+    };
+
+    let rows = lines[..=last]
+        .iter()
+        .map(|&(line_index, line)| {
+            let line_no = line_index + 1;
+            line.chars()
+                .enumerate()
+                .map(|(j, c)| Tile::from_char(c).ok_or_else(|| {
+                    StageParseError::at(StageParseErrorKind::UnknownTile(c), line_no, j + 1)
+                }))
+                .collect::<Result<Vec<_>, _>>()
+                .map(|row| (line_no, row))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    build_stage(rows)
+}
+
+/// Check the shape of the map and the number of players and goals.
+/// `rows` are the tiles of each line, with the 1-based line number used in errors.
+pub(super) fn build_stage(rows: Vec<(usize, Vec<Tile>)>) -> Result<Stage, StageParseError> {
+    if rows.is_empty() {
+        return Err(StageParseError::whole_file(StageParseErrorKind::EmptyFile));
+    }
+
+    let expected = rows[0].1.len();
+    let mut tiles = Vec::with_capacity(rows.len());
+    let mut player: Option<((i32, i32), (usize, usize))> = None;
+    let mut has_goal = false;
+    let (mut boxes, mut pads) = (0, 0);
+    // `(line, column)` of the first occurrence and count of each teleporter digit
+    let mut teleporters: Vec<(char, (usize, usize), usize)> = Vec::new();
+
+    for (line_no, row) in rows {
+        if row.len() != expected {
+            return Err(StageParseError::at(
+                StageParseErrorKind::RaggedRow { expected, found: row.len() },
+                line_no,
+                row.len().min(expected) + 1,
+            ));
+        }
+        for (j, &tile) in row.iter().enumerate() {
+            match tile {
+                Tile::Player => {
+                    if let Some((_, first)) = player {
+                        return Err(StageParseError::at(
+                            StageParseErrorKind::DuplicatePlayer { first },
+                            line_no,
+                            j + 1,
+                        ));
+                    }
+                    player = Some(((tiles.len() as i32, j as i32), (line_no, j + 1)));
+                }
+                Tile::Goal => has_goal = true,
+                Tile::Teleporter(digit) => match teleporters.iter_mut().find(|(other, _, _)| *other == digit) {
+                    Some((_, _, count)) => *count += 1,
+                    None => teleporters.push((digit, (line_no, j + 1), 1)),
+                },
+                _ => (),
+            }
+            boxes += tile.has_box() as usize;
+            pads += tile.is_pad() as usize;
+        }
+        tiles.push(row);
+    }
+
+    let player = match player {
+        Some((position, _)) => position,
+        None => return Err(StageParseError::whole_file(StageParseErrorKind::MissingPlayer)),
+    };
+    if !has_goal {
+        return Err(StageParseError::whole_file(StageParseErrorKind::MissingGoal));
+    }
+    if boxes < pads {
+        return Err(StageParseError::whole_file(StageParseErrorKind::TooFewBoxes { boxes, pads }));
+    }
+    if let Some(&(digit, (line, column), count)) = teleporters.iter().find(|(_, _, count)| *count != 2) {
+        return Err(StageParseError::at(
+            StageParseErrorKind::UnpairedTeleporter { digit, count },
+            line,
+            column,
+        ));
+    }
+
+    Ok(Stage {
+        height: tiles.len() as i32,
+        width: tiles[0].len() as i32,
+        tiles,
+        player,
+        patrols: Vec::new(),
+        hunters: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the kind and `(line, column)` of the error `source` fails with
+    fn error(source: &str) -> (StageParseErrorKind, (Option<usize>, Option<usize>)) {
+        let error = parse_stage(source).unwrap_err();
+        (error.kind, (error.line, error.column))
+    }
+
+    #[test]
+    fn blank_files_are_empty() {
+        assert_eq!(error(""), (StageParseErrorKind::EmptyFile, (None, None)));
+        assert_eq!(error("\n   \n"), (StageParseErrorKind::EmptyFile, (None, None)));
+    }
+
+    #[test]
+    fn unknown_tiles_are_located() {
+        assert_eq!(error("@.G\n.?."), (StageParseErrorKind::UnknownTile('?'), (Some(2), Some(2))));
+        // leading blank lines still count
+        assert_eq!(error("\n\n@%G"), (StageParseErrorKind::UnknownTile('%'), (Some(3), Some(2))));
+    }
+
+    #[test]
+    fn rows_must_match_the_first_one() {
+        let kind = StageParseErrorKind::RaggedRow { expected: 3, found: 2 };
+        assert_eq!(error("@.G\n.."), (kind, (Some(2), Some(3))));
+        let kind = StageParseErrorKind::RaggedRow { expected: 3, found: 4 };
+        assert_eq!(error("@.G\n...."), (kind, (Some(2), Some(4))));
+    }
+
+    #[test]
+    fn exactly_one_player() {
+        assert_eq!(error("..G"), (StageParseErrorKind::MissingPlayer, (None, None)));
+        let kind = StageParseErrorKind::DuplicatePlayer { first: (1, 1) };
+        assert_eq!(error("@.G\n.@."), (kind, (Some(2), Some(2))));
+    }
+
+    #[test]
+    fn at_least_one_goal() {
+        assert_eq!(error("@.."), (StageParseErrorKind::MissingGoal, (None, None)));
+    }
+
+    #[test]
+    fn every_pad_needs_a_box() {
+        let kind = StageParseErrorKind::TooFewBoxes { boxes: 1, pads: 2 };
+        assert_eq!(error("@$+\n+.G"), (kind, (None, None)));
+        // a box on a pad counts as both
+        assert!(parse_stage("@$+\n*.G").is_ok());
+    }
+
+    #[test]
+    fn teleporters_come_in_pairs() {
+        assert!(parse_stage("@1.1G\n22...").is_ok());
+
+        let error = parse_stage("@1..G\n.3.1.\n..1..").unwrap_err();
+        assert_eq!(error.kind, StageParseErrorKind::UnpairedTeleporter { digit: '1', count: 3 });
+        assert_eq!((error.line, error.column), (Some(1), Some(2)));
+
+        let error = parse_stage("@1.1G\n..2..").unwrap_err();
+        assert_eq!(error.kind, StageParseErrorKind::UnpairedTeleporter { digit: '2', count: 1 });
+    }
+
+    #[test]
+    fn door_v_is_unknown() {
+        let error = parse_stage("@.G\n.V.").unwrap_err();
+        assert_eq!(error.kind, StageParseErrorKind::UnknownTile('V'));
+        assert_eq!((error.line, error.column), (Some(2), Some(2)));
+        assert!(error.to_string().contains("'v' is the down arrow"));
+    }
+}
@@ -0,0 +1,117 @@
+//! Patrol routes of the enemies, given next to a stage in the manifest.
+use std::fmt;
+
+use serde::Deserialize;
+
+use super::Stage;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum PatrolMode {
+    /// walk to the last waypoint, then back to the first one, and so on
+    BackAndForth,
+    /// walk from the last waypoint straight back to the first one
+    Loop,
+}
+
+/// Route of one enemy. Consecutive waypoints, and the last and first ones of a `Loop`,
+/// must share a line or a column with no wall between them.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Patrol {
+    pub mode: PatrolMode,
+    /// `(y, x)` stage positions, the enemy starts on the first one
+    pub waypoints: Vec<(i32, i32)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PatrolErrorKind {
+    NoWaypoint,
+    /// a waypoint, or a cell between two of them, is a wall or outside of the stage
+    Blocked((i32, i32)),
+    /// two waypoints which share neither a line nor a column
+    NotStraight { from: (i32, i32), to: (i32, i32) },
+}
+
+/// Error about the patrol of the enemy at index `enemy` in the manifest entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatrolError {
+    pub enemy: usize,
+    pub kind: PatrolErrorKind,
+}
+
+impl fmt::Display for PatrolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "enemy {}: ", self.enemy + 1)?;
+        match self.kind {
+            PatrolErrorKind::NoWaypoint => write!(f, "patrol has no waypoint"),
+            PatrolErrorKind::Blocked((y, x)) => write!(f, "patrol crosses a wall at line {}, column {}", y + 1, x + 1),
+            PatrolErrorKind::NotStraight { from, to } => write!(
+                f, "waypoints {:?} and {:?} are not on the same line or column", from, to
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatrolError {}
+
+impl Patrol {
+    /// cells the enemy stands on turn after turn, repeating forever
+    pub fn cycle(&self, stage: &Stage) -> Result<Vec<(i32, i32)>, PatrolErrorKind> {
+        let first = *self.waypoints.first().ok_or(PatrolErrorKind::NoWaypoint)?;
+        let open = |position| stage.tile(position).is_some_and(|tile| !tile.is_obstacle());
+        if !open(first) {
+            return Err(PatrolErrorKind::Blocked(first));
+        }
+
+        let mut path = vec![first];
+        let closing = match self.mode {
+            PatrolMode::Loop if self.waypoints.len() > 1 => Some(first),
+            _ => None,
+        };
+        for &to in self.waypoints[1..].iter().chain(closing.iter()) {
+            let from = *path.last().unwrap();
+            if from.0 != to.0 && from.1 != to.1 {
+                return Err(PatrolErrorKind::NotStraight { from, to });
+            }
+            let step = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+            let mut position = from;
+            while position != to {
+                position = (position.0 + step.0, position.1 + step.1);
+                if !open(position) {
+                    return Err(PatrolErrorKind::Blocked(position));
+                }
+                path.push(position);
+            }
+        }
+
+        match self.mode {
+            // the first cell comes back at the start of the next cycle
+            PatrolMode::Loop if path.len() > 1 => {
+                path.pop();
+            }
+            PatrolMode::Loop => (),
+            PatrolMode::BackAndForth => {
+                let back = path.iter().rev().skip(1).take(path.len().saturating_sub(2)).copied().collect::<Vec<_>>();
+                path.extend(back);
+            }
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_waypoints_into_a_cycle() {
+        let stage: Stage = "@....\n.#...\n....G".parse().unwrap();
+        let back_and_forth = Patrol { mode: PatrolMode::BackAndForth, waypoints: vec![(0, 1), (0, 3)] };
+        assert_eq!(back_and_forth.cycle(&stage).unwrap(), vec![(0, 1), (0, 2), (0, 3), (0, 2)]);
+
+        let looped = Patrol { mode: PatrolMode::Loop, waypoints: vec![(0, 2), (0, 4), (2, 4), (2, 2)] };
+        assert_eq!(looped.cycle(&stage).unwrap().len(), 8);
+
+        let through_wall = Patrol { mode: PatrolMode::BackAndForth, waypoints: vec![(0, 1), (2, 1)] };
+        assert_eq!(through_wall.cycle(&stage).unwrap_err(), PatrolErrorKind::Blocked((1, 1)));
+    }
+}
@@ -0,0 +1,340 @@
+//! Shortest path from the player to a goal.
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+use crate::{
+    grid::Direction,
+    rules::{hunt, is_caught, is_cleared, plan_move, Board},
+};
+
+use super::{Stage, Tile};
+
+/// Proof that a stage cannot be cleared: every cell the player can reach,
+/// none of them a goal it can stand on with every pad covered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unsolvable {
+    pub reachable: Vec<(i32, i32)>,
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no goal among the {} cells the player can reach", self.reachable.len())
+    }
+}
+
+impl std::error::Error for Unsolvable {}
+
+/// number of moves after which every enemy is back to its start
+fn patrol_period(stage: &Stage) -> usize {
+    fn gcd(a: usize, b: usize) -> usize {
+        if b == 0 { a } else { gcd(b, a % b) }
+    }
+    stage.patrols().iter().fold(1, |period, cycle| period / gcd(period, cycle.len()) * cycle.len())
+}
+
+/// bit of a key letter in the set of collected keys
+fn key_bit(letter: char) -> u32 {
+    1 << (letter as u32 - 'a' as u32)
+}
+
+/// Everything a move can change on the stage.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct SearchState {
+    player: (i32, i32),
+    /// `key_bit`s of the collected keys
+    keys: u32,
+    /// positions of the boxes, sorted so that equal layouts compare equal
+    boxes: Vec<(i32, i32)>,
+    /// moves played, modulo the length shared by every patrol cycle
+    turn: usize,
+    /// positions of the hunters, in the order of the manifest
+    hunters: Vec<(i32, i32)>,
+}
+
+impl SearchState {
+    /// whether the player or a box can enter `position`, before counting the boxes
+    fn is_open(&self, stage: &Stage, position: (i32, i32)) -> bool {
+        match stage.tile(position) {
+            Some(Tile::Door(letter)) => self.keys & key_bit(letter) != 0,
+            Some(tile) => !tile.is_obstacle(),
+            None => false,
+        }
+    }
+
+    /// the state after the player moves towards `direction`, `None` if it bumps or gets caught
+    fn step(&self, stage: &Stage, partners: &HashMap<(i32, i32), (i32, i32)>, direction: Direction) -> Option<Self> {
+        let board = StateBoard { stage, partners, state: self };
+        let planned = plan_move(&board, &self.player, direction)?;
+
+        let mut state = self.clone();
+        for &cell in &planned.path {
+            if let Some(Tile::Key(letter)) = stage.tile(cell) {
+                state.keys |= key_bit(letter);
+            }
+        }
+        state.player = *planned.end();
+        if let Some((from, to)) = planned.pushed {
+            let i = state.boxes.binary_search(&from).unwrap();
+            state.boxes[i] = to;
+            state.boxes.sort_unstable();
+        }
+
+        // then every enemy takes its step
+        state.turn = (self.turn + 1) % patrol_period(stage);
+        let mut caught = stage.patrols().iter().any(|cycle| {
+            let before = cycle[self.turn % cycle.len()];
+            let after = cycle[state.turn % cycle.len()];
+            is_caught(&state.player, &before, &after)
+        });
+        let board = StateBoard { stage, partners, state: &state };
+        let hunters = state.hunters.iter()
+            .zip(stage.hunters())
+            .map(|(from, hunter)| {
+                let to = hunt(&board, from, &state.player, hunter.speed, hunter.vision);
+                caught |= is_caught(&state.player, from, &to);
+                to
+            })
+            .collect();
+        state.hunters = hunters;
+        if caught {
+            return None;
+        }
+        Some(state)
+    }
+
+    fn is_won(&self, stage: &Stage, partners: &HashMap<(i32, i32), (i32, i32)>, goals: &[(i32, i32)], pads: &[(i32, i32)]) -> bool {
+        is_cleared(&StateBoard { stage, partners, state: self }, &self.player, goals, pads)
+    }
+}
+
+/// The stage as the rules see it in a search state.
+struct StateBoard<'a> {
+    stage: &'a Stage,
+    /// maps each teleporter to the other one with the same digit
+    partners: &'a HashMap<(i32, i32), (i32, i32)>,
+    state: &'a SearchState,
+}
+
+impl Board<(i32, i32)> for StateBoard<'_> {
+    fn is_blocked(&self, &cell: &(i32, i32)) -> bool {
+        !self.state.is_open(self.stage, cell)
+    }
+
+    fn has_box(&self, cell: &(i32, i32)) -> bool {
+        self.state.boxes.binary_search(cell).is_ok()
+    }
+
+    fn has_key(&self, &cell: &(i32, i32)) -> bool {
+        matches!(self.stage.tile(cell), Some(Tile::Key(letter)) if self.state.keys & key_bit(letter) == 0)
+    }
+
+    fn is_ice(&self, &cell: &(i32, i32)) -> bool {
+        self.stage.tile(cell) == Some(Tile::Ice)
+    }
+
+    fn allows(&self, &cell: &(i32, i32), direction: Direction) -> bool {
+        self.stage.tile(cell).is_some_and(|tile| tile.allows(direction))
+    }
+
+    fn teleporter_partner(&self, cell: &(i32, i32)) -> Option<(i32, i32)> {
+        self.partners.get(cell).copied()
+    }
+}
+
+/// Breadth-first search over the stage, playing by the `rules` like the game does:
+/// doors block the player until their key is collected and enemies must not catch it.
+/// The search runs over every layout of the player, the keys, the boxes and the hunters.
+/// Returns one of the shortest move sequences from `@` to a `G` with every pad covered.
+pub fn solve(stage: &Stage) -> Result<Vec<Direction>, Unsolvable> {
+    solve_within(stage, usize::MAX).expect("a search without a budget always ends")
+}
+
+/// Like `solve`, but gives up with `None` once more than `max_states` layouts were reached
+/// without an answer, since boxes and hunters can make them too many to search.
+pub fn solve_within(stage: &Stage, max_states: usize) -> Option<Result<Vec<Direction>, Unsolvable>> {
+    let (height, width) = stage.size();
+    let index = |(y, x): (i32, i32)| (y * width + x) as usize;
+
+    let mut boxes = stage.positions_of(Tile::has_box);
+    boxes.sort_unstable();
+    let hunters = stage.hunters().iter().map(|hunter| hunter.start).collect();
+    let start = SearchState { player: stage.player(), keys: 0, boxes, turn: 0, hunters };
+    let partners = stage.cells()
+        .filter_map(|(position, _)| Some((position, stage.teleporter_partner(position)?)))
+        .collect::<HashMap<_, _>>();
+    let goals = stage.positions_of(|tile| tile == Tile::Goal);
+    let pads = stage.positions_of(Tile::is_pad);
+
+    // the move which first reached each state, with the state before it
+    let mut came_by: HashMap<SearchState, (Direction, SearchState)> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut reachable = vec![false; (height * width) as usize];
+    let mut queue = VecDeque::new();
+    visited.insert(start.clone());
+    queue.push_back(start);
+
+    while let Some(state) = queue.pop_front() {
+        if visited.len() > max_states {
+            return None;
+        }
+        reachable[index(state.player)] = true;
+        if state.is_won(stage, &partners, &goals, &pads) {
+            let mut path = Vec::new();
+            let mut state = &state;
+            while let Some((direction, previous)) = came_by.get(state) {
+                path.push(*direction);
+                state = previous;
+            }
+            path.reverse();
+            return Some(Ok(path));
+        }
+
+        for &direction in &Direction::ALL {
+            if let Some(next) = state.step(stage, &partners, direction) {
+                if visited.insert(next.clone()) {
+                    came_by.insert(next.clone(), (direction, state.clone()));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    let reachable = stage.cells()
+        .map(|(position, _)| position)
+        .filter(|&position| reachable[index(position)])
+        .collect();
+    Some(Err(Unsolvable { reachable }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::StageManifest;
+
+    #[test]
+    fn every_shipped_stage_is_solvable() {
+        let manifest = StageManifest::load("resources/stages/manifest.ron").unwrap();
+        for i in 0..manifest.len() {
+            let entry = manifest.get(i).unwrap();
+            let stage = manifest.load_stage(entry).unwrap();
+            if let Err(e) = solve(&stage) {
+                panic!("stage {:?} is not solvable: {}", entry.id, e);
+            }
+        }
+    }
+
+    #[test]
+    fn finds_a_shortest_path() {
+        let stage: Stage = ".....\n.###.\n@#G..".parse().unwrap();
+        assert_eq!(
+            solve(&stage).unwrap(),
+            vec![
+                Direction::Down, Direction::Down, Direction::Right, Direction::Right,
+                Direction::Right, Direction::Right, Direction::Up, Direction::Up,
+                Direction::Left, Direction::Left,
+            ]
+        );
+    }
+
+    #[test]
+    fn collects_keys_before_going_through_doors() {
+        let stage: Stage = "a.@A.G".parse().unwrap();
+        assert_eq!(solve(&stage).unwrap().len(), 7);
+
+        let stage: Stage = "b.@A.G".parse().unwrap();
+        assert_eq!(solve(&stage).unwrap_err().reachable.len(), 3);
+    }
+
+    #[test]
+    fn pushes_boxes_onto_every_pad() {
+        let stage: Stage = "@$.+\n...G".parse().unwrap();
+        assert_eq!(solve(&stage).unwrap().len(), 4);
+
+        // the box would have to be pulled back out of the corner
+        let stage: Stage = "+..\n.$.\n@.G".parse().unwrap();
+        assert!(solve(&stage).is_ok());
+        let stage: Stage = "..$\n+..\n@.G".parse().unwrap();
+        assert!(solve(&stage).is_err());
+    }
+
+    #[test]
+    fn slides_over_ice_until_blocked() {
+        // sliding right stops on the floor past the ice, the goal is only reached from the right
+        let stage: Stage = "@~~.G\n~~~#.".parse().unwrap();
+        assert_eq!(solve(&stage).unwrap(), vec![Direction::Right, Direction::Right]);
+
+        // the slide over the bottom row can't stop below the goal
+        let stage: Stage = "#G#\n@~~".parse().unwrap();
+        assert!(solve(&stage).is_err());
+    }
+
+    #[test]
+    fn teleports_to_the_partner() {
+        let stage: Stage = "@1#.2\n##..#\nG2#1.".parse().unwrap();
+        assert_eq!(
+            solve(&stage).unwrap(),
+            vec![Direction::Right, Direction::Down, Direction::Down, Direction::Right, Direction::Left],
+        );
+    }
+
+    #[test]
+    fn crosses_arrows_in_their_direction_only() {
+        let stage: Stage = "@>G".parse().unwrap();
+        assert_eq!(solve(&stage).unwrap(), vec![Direction::Right, Direction::Right]);
+
+        let stage: Stage = "G<@".parse().unwrap();
+        assert!(solve(&stage).is_ok());
+        let stage: Stage = "G>@".parse().unwrap();
+        assert!(solve(&stage).is_err());
+
+        // entering the arrow sideways is refused, the way around is longer
+        let stage: Stage = "@^G\n...".parse().unwrap();
+        assert_eq!(solve(&stage).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn waits_for_patrols_to_pass() {
+        use super::super::patrol::{Patrol, PatrolMode};
+
+        // the enemy crosses the corridor on turns 1 and 5 of 6,
+        // so the player steps back once to get there on turn 4
+        let patrol = Patrol { mode: PatrolMode::BackAndForth, waypoints: vec![(0, 2), (3, 2)] };
+        let stage: Stage = "##.##\n@...G\n##.##\n##.##".parse().unwrap();
+        let stage = stage.with_patrols(&[patrol]).unwrap();
+        assert_eq!(solve(&stage).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn runs_away_from_hunters() {
+        use super::super::hunter::Hunter;
+
+        // as fast as the player, the hunter never catches up while it walks around the pillar
+        let stage: Stage = "@...\n.##.\n...G".parse().unwrap();
+        let hunter = Hunter { start: (0, 3), speed: 1, vision: u32::MAX };
+        let chased = stage.clone().with_hunters(std::slice::from_ref(&hunter)).unwrap();
+        assert_eq!(solve(&chased).unwrap(), vec![Direction::Up, Direction::Up, Direction::Right, Direction::Right, Direction::Right]);
+
+        // twice as fast, it catches the player on either side
+        let fast = Hunter { speed: 2, ..hunter.clone() };
+        assert!(solve(&stage.clone().with_hunters(&[fast]).unwrap()).is_err());
+
+        // unless it only sees the cells next to it, where the player never goes
+        let short_sighted = Hunter { speed: 2, vision: 1, ..hunter };
+        assert!(solve(&stage.with_hunters(&[short_sighted]).unwrap()).is_ok());
+    }
+
+    #[test]
+    fn gives_up_past_the_state_budget() {
+        let stage: Stage = ".....\n.....\n@...G".parse().unwrap();
+        assert_eq!(solve_within(&stage, 5), None);
+        assert_eq!(solve_within(&stage, 15).unwrap().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn reports_reachable_cells_of_an_unsolvable_stage() {
+        let stage: Stage = "@.#G\n.#..".parse().unwrap();
+        assert_eq!(solve(&stage).unwrap_err().reachable.len(), 3);
+    }
+}
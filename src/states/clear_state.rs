@@ -2,7 +2,7 @@ use amethyst::{
     prelude::*,
     assets::{Handle, Loader},
     // ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
-    input::{is_key_down, VirtualKeyCode},
+    input::InputEvent,
     renderer::{
         palette::{Srgba, Pixel},
     },
@@ -12,17 +12,29 @@ use amethyst::{
 };
 
 use crate::{
-    config::{DEFAULT_GRID_SIZE, CELL_SIZE},
-    states::PlayState,
-    resources::CurrentStageData,
+    config::{actions, DEFAULT_GRID_SIZE, CELL_SIZE},
+    states::{key_hints::key_names, EndingState, PlayState},
+    resources::{CurrentStageData, GameState, SaveData, StageManifest, StageStats},
 };
 
+/// keep the records of the run and unlock the next stage
+fn save_clear(world: &mut World) {
+    let stage = world.try_fetch::<CurrentStageData>().unwrap().stage;
+    let stats = world.read_resource::<StageStats>();
+    let mut save_data = world.write_resource::<SaveData>();
+    save_data.record_clear(&world.read_resource::<StageManifest>(), stage, stats.moves, stats.elapsed);
+    if let Err(e) = save_data.store() {
+        log::warn!("failed to save progress: {}", e);
+    }
+}
+
 pub struct ClearState;
 
 impl SimpleState for ClearState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
-        
+        save_clear(world);
+
         let map_size = (
             DEFAULT_GRID_SIZE * CELL_SIZE,
             DEFAULT_GRID_SIZE * CELL_SIZE,
@@ -57,45 +69,72 @@ impl SimpleState for ClearState {
             &world.read_resource(),
         );
 
-        world
-            .create_entity()
-            .with(UiTransform::new(
-                "P2".to_string(), Anchor::Middle, Anchor::Middle,
-                0., 20., 9.5, 500., 200.,
-            ))
-            .with(UiText::new(
-                font_handle.clone(),
-                "Stage Clear".to_string(),
-                [1., 1., 1., 1.],
-                40.,
-                LineMode::Single,
-                Anchor::Middle,
-            ))
-            .build();
+        let stage_title = {
+            let stage = world.try_fetch::<CurrentStageData>().unwrap().stage;
+            let manifest = world.read_resource::<StageManifest>();
+            manifest.get(stage)
+                .map(|entry| format!("{} - {}", entry.chapter, entry.name))
+                .unwrap_or_default()
+        };
 
-            world
-            .create_entity()
-            .with(UiTransform::new(
-                "P2".to_string(), Anchor::Middle, Anchor::Middle,
-                0., -20., 9.5, 500., 200.,
-            ))
-            .with(UiText::new(
-                font_handle.clone(),
-                "press enter".to_string(),
-                [1., 1., 1., 1.],
-                20.,
-                LineMode::Single,
-                Anchor::Middle,
-            ))
-            .build();
+        let stats = StageStats::clone(&world.read_resource());
+        let moves = match stats.optimal_moves {
+            Some(optimal) => format!("moves {} (shortest {})", stats.moves, optimal),
+            None => format!("moves {}", stats.moves),
+        };
+        let stars = stats.stars().map(|stars| "*".repeat(stars as usize)).unwrap_or_default();
+
+        let lines = [
+            (stage_title, 20., 130.),
+            ("Stage Clear".to_string(), 40., 80.),
+            (moves, 20., 30.),
+            (format!("time {}", stats.elapsed_text()), 20., 5.),
+            (format!("bumps {}", stats.bumps), 20., -20.),
+            (stars, 40., -65.),
+            (
+                format!(
+                    "{}: next    {}: retry",
+                    key_names(world, actions::CONFIRM), key_names(world, actions::RESTART)
+                ),
+                20., -120.,
+            ),
+        ];
 
+        for (i, (message, font_size, y)) in lines.iter().enumerate() {
+            world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("clear{}", i), Anchor::Middle, Anchor::Middle,
+                    0., *y, 9.5, 500., 50.,
+                ))
+                .with(UiText::new(
+                    font_handle.clone(),
+                    message.clone(),
+                    [1., 1., 1., 1.],
+                    *font_size,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build();
+        }
     }
 
     fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
-        if let StateEvent::Window(ref event) = event {
-            if is_key_down(event, VirtualKeyCode::Return) {
-                data.world.try_fetch_mut::<CurrentStageData>().unwrap().next_stage();
-                return Trans::Switch(Box::new(PlayState));
+        if let StateEvent::Input(InputEvent::ActionPressed(ref action)) = event {
+            let mut stage_data = data.world.try_fetch_mut::<CurrentStageData>().unwrap();
+            match action.as_str() {
+                actions::CONFIRM => {
+                    stage_data.next_stage(&data.world.read_resource::<StageManifest>());
+                    if stage_data.state == GameState::AllClear {
+                        return Trans::Switch(Box::new(EndingState));
+                    }
+                    return Trans::Switch(Box::new(PlayState));
+                }
+                actions::RESTART => {
+                    stage_data.restart();
+                    return Trans::Switch(Box::new(PlayState));
+                }
+                _ => (),
             }
         }
         Trans::None
@@ -0,0 +1,77 @@
+use amethyst::{
+    prelude::*,
+    assets::{Handle, Loader},
+    input::{is_close_requested, InputEvent},
+    ui::{Anchor, FontAsset, LineMode, TtfFormat, UiText, UiTransform},
+};
+
+use crate::{
+    config::actions,
+    states::{key_hints::key_names, LoadState},
+};
+
+/// Shown once every stage of the manifest has been cleared.
+pub struct EndingState;
+
+impl SimpleState for EndingState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.delete_all();
+
+        let font_handle: Handle<FontAsset> = world.read_resource::<Loader>().load(
+            "fonts/square.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                "all clear".to_string(), Anchor::Middle, Anchor::Middle,
+                0., 20., 9.5, 500., 200.,
+            ))
+            .with(UiText::new(
+                font_handle.clone(),
+                "All Stages Clear".to_string(),
+                [1., 1., 1., 1.],
+                40.,
+                LineMode::Single,
+                Anchor::Middle,
+            ))
+            .build();
+
+        let hint = format!("press {}", key_names(world, actions::CONFIRM));
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                "press enter".to_string(), Anchor::Middle, Anchor::Middle,
+                0., -40., 9.5, 500., 200.,
+            ))
+            .with(UiText::new(
+                font_handle,
+                hint,
+                [1., 1., 1., 1.],
+                20.,
+                LineMode::Single,
+                Anchor::Middle,
+            ))
+            .build();
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent
+    ) -> SimpleTrans {
+        match event {
+            StateEvent::Window(ref event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(ref action)) => match action.as_str() {
+                actions::CONFIRM => Trans::Switch(Box::new(LoadState)),
+                actions::PAUSE => Trans::Quit,
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
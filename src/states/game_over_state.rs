@@ -0,0 +1,98 @@
+use amethyst::{
+    prelude::*,
+    assets::{Handle, Loader},
+    input::{is_close_requested, InputEvent},
+    renderer::palette::{Pixel, Srgba},
+    ui::{Anchor, FontAsset, LineMode, TtfFormat, UiImage, UiText, UiTransform},
+};
+
+use crate::{
+    config::actions,
+    resources::{CurrentStageData, StageManifest, StageStats},
+    states::{key_hints::key_names, LoadState, PlayState},
+};
+
+/// Shown over the stage when an enemy caught the player.
+pub struct GameOverState;
+
+impl SimpleState for GameOverState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        world.create_entity()
+            .with(UiTransform::new(
+                "game over screen".to_string(), Anchor::Middle, Anchor::Middle,
+                0., 0., 9., 550., 550.,
+            ))
+            .with(UiImage::SolidColor(
+                Srgba::new(0.12, 0.02, 0.04, 0.8)
+                    .into_linear()
+                    .into_raw()
+            ))
+            .build();
+
+        let font_handle: Handle<FontAsset> = world.read_resource::<Loader>().load(
+            "fonts/square.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+
+        let stage_title = {
+            let stage = world.try_fetch::<CurrentStageData>().unwrap().stage;
+            let manifest = world.read_resource::<StageManifest>();
+            manifest.get(stage)
+                .map(|entry| format!("{} - {}", entry.chapter, entry.name))
+                .unwrap_or_default()
+        };
+        let moves = world.read_resource::<StageStats>().moves;
+
+        let lines = [
+            (stage_title, 20., 90.),
+            ("Caught!".to_string(), 40., 40.),
+            (format!("after {} moves", moves), 20., -10.),
+            (
+                format!(
+                    "{}/{}: retry    {}: title",
+                    key_names(world, actions::CONFIRM),
+                    key_names(world, actions::RESTART),
+                    key_names(world, actions::TITLE),
+                ),
+                20., -80.,
+            ),
+        ];
+
+        for (i, (message, font_size, y)) in lines.iter().enumerate() {
+            world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("game over{}", i), Anchor::Middle, Anchor::Middle,
+                    0., *y, 9.5, 500., 50.,
+                ))
+                .with(UiText::new(
+                    font_handle.clone(),
+                    message.clone(),
+                    [1., 1., 1., 1.],
+                    *font_size,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build();
+        }
+    }
+
+    fn handle_event(&mut self, data: StateData<'_, GameData<'_, '_>>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Window(ref event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(ref action)) => match action.as_str() {
+                actions::CONFIRM | actions::RESTART => {
+                    data.world.try_fetch_mut::<CurrentStageData>().unwrap().restart();
+                    Trans::Switch(Box::new(PlayState))
+                }
+                actions::TITLE => Trans::Switch(Box::new(LoadState)),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
@@ -0,0 +1,53 @@
+//! Key hints built from the bindings of `resources/input.ron`, so that they follow its presets.
+use amethyst::{
+    prelude::*,
+    input::{Button, InputHandler, StringBindings, VirtualKeyCode},
+};
+
+fn button_name(button: &Button) -> String {
+    match button {
+        Button::Key(VirtualKeyCode::Return) => "enter".to_string(),
+        Button::Key(VirtualKeyCode::Escape) => "esc".to_string(),
+        Button::Key(VirtualKeyCode::Back) => "backspace".to_string(),
+        // letters as they are printed on the keys
+        Button::Key(key) => {
+            let name = format!("{:?}", key);
+            if name.len() == 1 { name } else { name.to_lowercase() }
+        }
+        other => format!("{:?}", other).to_lowercase(),
+    }
+}
+
+/// the keys bound to `action`, like `enter/space`, or `?` when it is unbound
+pub fn key_names(world: &World, action: &str) -> String {
+    let input = world.read_resource::<InputHandler<StringBindings>>();
+    let names = input.bindings.action_bindings(action)
+        .map(|combination| combination.iter().map(button_name).collect::<Vec<_>>().join("+"))
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        "?".to_string()
+    } else {
+        names.join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use amethyst::{config::Config, input::Bindings};
+
+    use super::*;
+    use crate::config::actions;
+
+    #[test]
+    fn names_the_keys_of_the_preset() {
+        let mut input = InputHandler::<StringBindings>::new();
+        input.bindings = Bindings::load("resources/input/vi.ron").unwrap();
+        let mut world = World::new();
+        world.insert(input);
+
+        assert_eq!(key_names(&world, actions::UNDO), "U/backspace");
+        assert_eq!(key_names(&world, actions::CONFIRM), "enter/space");
+        assert_eq!(key_names(&world, actions::PAUSE), "esc/P");
+        assert_eq!(key_names(&world, "unbound"), "?");
+    }
+}
@@ -2,20 +2,22 @@ use amethyst::{
     prelude::*,
     core::transform::Transform,
     assets::{Handle, Loader},
-    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    input::{is_close_requested, InputEvent},
     renderer::Camera,
     ui::{Anchor, FontAsset, LineMode, TtfFormat, UiText, UiTransform},
 };
 
 use crate::{
-    components::basics::{Wall, WallInvisible},
-    config::{DEFAULT_GRID_SIZE, CELL_SIZE},
-    resources::CurrentStageData,
-    states::PlayState,
+    components::basics::{Goal, Pad, Wall},
+    config::{actions, DEFAULT_GRID_SIZE, CELL_SIZE},
+    resources::{CurrentStageData, SaveData, StageManifest},
+    states::{key_hints::key_names, StageSelectState},
 };
 
 fn set_resources(world: &mut World) {
-    world.insert(CurrentStageData::default());
+    let stage = world.read_resource::<SaveData>()
+        .resume_stage(&world.read_resource::<StageManifest>());
+    world.insert(CurrentStageData { stage, ..Default::default() });
 }
 
 fn create_start_window(world: &mut World) {
@@ -70,7 +72,7 @@ fn create_start_window(world: &mut World) {
 
         let text = UiText::new(
             font_handle.clone(),
-            "press enter".to_string(),
+            format!("press {}", key_names(world, actions::CONFIRM)),
             [1., 1., 1., 1.],
             36.,
             LineMode::Single,
@@ -95,7 +97,9 @@ impl SimpleState for LoadState {
         world.delete_all();
 
         world.register::<Wall>();
-        world.register::<WallInvisible>();
+        // only drawn, `WinSystem` finds them in `StageTargets`
+        world.register::<Goal>();
+        world.register::<Pad>();
 
         create_start_window(world)
     }
@@ -105,13 +109,15 @@ impl SimpleState for LoadState {
         _data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent
     ) -> SimpleTrans {
-        if let StateEvent::Window(ref event) = event {
-            if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) {
-                return Trans::Quit
-            } else if is_key_down(event, VirtualKeyCode::Return) {
-                return Trans::Switch(Box::new(PlayState));
-            }
+        match event {
+            StateEvent::Window(ref event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(ref action)) => match action.as_str() {
+                actions::CONFIRM => Trans::Switch(Box::<StageSelectState>::default()),
+                // back from the title screen, like from the other menus
+                actions::PAUSE => Trans::Quit,
+                _ => Trans::None,
+            },
+            _ => Trans::None,
         }
-        Trans::None
     }
 }
\ No newline at end of file
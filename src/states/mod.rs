@@ -1,7 +1,18 @@
 mod play_state;
 mod clear_state;
 mod load_state;
+mod ending_state;
+mod game_over_state;
+mod key_hints;
+mod pause_state;
+mod settings_state;
+mod stage_select_state;
 
-pub use play_state::PlayState;
+pub use play_state::{spawn_stage, PlayState};
 pub use clear_state::ClearState;
-pub use load_state::LoadState;
\ No newline at end of file
+pub use load_state::LoadState;
+pub use ending_state::EndingState;
+pub use game_over_state::GameOverState;
+pub use pause_state::PauseState;
+pub use settings_state::SettingsState;
+pub use stage_select_state::StageSelectState;
\ No newline at end of file
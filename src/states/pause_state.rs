@@ -0,0 +1,204 @@
+use amethyst::{
+    prelude::*,
+    assets::{Handle, Loader},
+    ecs::Entity,
+    input::{is_close_requested, InputEvent},
+    renderer::palette::{Pixel, Srgba},
+    ui::{Anchor, FontAsset, LineMode, TtfFormat, UiImage, UiText, UiTransform},
+};
+
+use crate::{
+    config::actions,
+    resources::{CurrentStageData, GameState},
+    states::{PlayState, SettingsState, StageSelectState},
+};
+
+const SELECTED_COLOR: [f32; 4] = [1., 0.8, 0.2, 1.];
+const TEXT_COLOR: [f32; 4] = [1., 1., 1., 1.];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MenuItem {
+    Resume,
+    Restart,
+    StageSelect,
+    Settings,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 5] = [
+        MenuItem::Resume,
+        MenuItem::Restart,
+        MenuItem::StageSelect,
+        MenuItem::Settings,
+        MenuItem::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::Resume => "Resume",
+            MenuItem::Restart => "Restart",
+            MenuItem::StageSelect => "Stage Select",
+            MenuItem::Settings => "Settings",
+            MenuItem::Quit => "Quit",
+        }
+    }
+}
+
+/// Pushed on top of `PlayState`. Systems see `GameState::Pause` and stay idle
+/// until the menu is closed.
+#[derive(Default)]
+pub struct PauseState {
+    selected: usize,
+    /// background, title, then one text per `MenuItem::ALL`
+    entities: Vec<Entity>,
+}
+
+impl PauseState {
+    fn move_cursor(&mut self, world: &mut World, up: bool) {
+        let len = MenuItem::ALL.len();
+        self.selected = if up { (self.selected + len - 1) % len } else { (self.selected + 1) % len };
+        self.update_colors(world);
+    }
+
+    fn update_colors(&self, world: &mut World) {
+        let mut texts = world.write_storage::<UiText>();
+        for (i, entity) in self.entities[2..].iter().enumerate() {
+            if let Some(text) = texts.get_mut(*entity) {
+                text.color = if i == self.selected { SELECTED_COLOR } else { TEXT_COLOR };
+            }
+        }
+    }
+
+    fn create_menu(&mut self, world: &mut World) {
+        let font_handle: Handle<FontAsset> = world.read_resource::<Loader>().load(
+            "fonts/square.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "pause background".to_string(), Anchor::Middle, Anchor::Middle,
+                0., 0., 9., 550., 550.,
+            ))
+            .with(UiImage::SolidColor(
+                Srgba::new(0.05, 0.02, 0.06, 0.8)
+                    .into_linear()
+                    .into_raw()
+            ))
+            .build();
+        self.entities.push(background);
+
+        let title = world
+            .create_entity()
+            .with(UiTransform::new(
+                "pause title".to_string(), Anchor::Middle, Anchor::Middle,
+                0., 120., 9.5, 500., 60.,
+            ))
+            .with(UiText::new(
+                font_handle.clone(),
+                "Pause".to_string(),
+                [1., 1., 1., 1.],
+                40.,
+                LineMode::Single,
+                Anchor::Middle,
+            ))
+            .build();
+        self.entities.push(title);
+
+        for (i, item) in MenuItem::ALL.iter().enumerate() {
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    item.label().to_string(), Anchor::Middle, Anchor::Middle,
+                    0., 50. - 40. * i as f32, 9.5, 500., 40.,
+                ))
+                .with(UiText::new(
+                    font_handle.clone(),
+                    item.label().to_string(),
+                    TEXT_COLOR,
+                    24.,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build();
+            self.entities.push(entity);
+        }
+        self.update_colors(world);
+    }
+
+    fn remove_menu(&mut self, world: &mut World) {
+        world.delete_entities(&self.entities).expect("failed to remove the pause menu");
+        self.entities.clear();
+    }
+
+    fn confirm(&self, world: &mut World) -> SimpleTrans {
+        match MenuItem::ALL[self.selected] {
+            MenuItem::Resume => Trans::Pop,
+            MenuItem::Restart => {
+                world.try_fetch_mut::<CurrentStageData>().unwrap().restart();
+                Trans::Replace(Box::new(PlayState))
+            }
+            // leave PlayState as well, StageSelectState clears the world
+            MenuItem::StageSelect => Trans::Sequence(vec![
+                Trans::Pop,
+                Trans::Switch(Box::<StageSelectState>::default()),
+            ]),
+            MenuItem::Settings => Trans::Push(Box::<SettingsState>::default()),
+            MenuItem::Quit => Trans::Quit,
+        }
+    }
+}
+
+impl SimpleState for PauseState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.try_fetch_mut::<CurrentStageData>().unwrap().state = GameState::Pause;
+        self.create_menu(world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        self.remove_menu(world);
+
+        let mut stage_data = world.try_fetch_mut::<CurrentStageData>().unwrap();
+        if stage_data.state == GameState::Pause {
+            stage_data.state = GameState::Play;
+        }
+    }
+
+    /// make room for the settings menu
+    fn on_pause(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.remove_menu(data.world);
+    }
+
+    fn on_resume(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.create_menu(data.world);
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent
+    ) -> SimpleTrans {
+        match event {
+            StateEvent::Window(ref event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(ref action)) => match action.as_str() {
+                actions::PAUSE => Trans::Pop,
+                actions::UP => {
+                    self.move_cursor(data.world, true);
+                    Trans::None
+                }
+                actions::DOWN => {
+                    self.move_cursor(data.world, false);
+                    Trans::None
+                }
+                actions::CONFIRM => self.confirm(data.world),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
@@ -1,24 +1,35 @@
 use amethyst::{
     prelude::*,
     assets::{AssetStorage, Handle, Loader},
-    core::transform::Transform,
-    input::{is_close_requested, is_key_down, VirtualKeyCode},
+    core::{transform::Transform, Parent},
+    input::{is_close_requested, InputEvent},
     renderer::{
         Camera, ImageFormat, SpriteRender,
         SpriteSheet, SpriteSheetFormat, Texture,
-        // palette::Srgba,
-        // resources::Tint,
+        palette::Srgba,
+        resources::Tint,
     },
+    ui::{Anchor, FontAsset, LineMode, TtfFormat, UiText, UiTransform},
 };
 
 use crate::{
     components::{
-        basics::{Goal, Obstacle, Player, Wall, WallInvisible},
-        grid2d::Grid2D,
+        basics::{
+            Door, Goal, Ice, Key, Obstacle, OneWay, Pad, Player, Pushable, Teleporter, Wall,
+        },
+        camera::{Background, Scrolling},
+        enemy::{Enemy, Hunter},
+        grid2d::{Direction, Grid2D},
+        hud::HudField,
+        inventory::Inventory,
     },
-    resources::{CurrentStageData, GameState},
-    config::{DEFAULT_GRID_SIZE, CELL_SIZE},
-    states::ClearState,
+    resources::{
+        CurrentStageData, GameState, MoveHistory, Occupancy, Solutions, StageManifest, StageStats,
+        StageTargets,
+    },
+    config::{actions, DEFAULT_GRID_SIZE, CELL_SIZE, MAX_VIEW_SIZE},
+    stage::{Stage, StageLoadError, Tile},
+    states::{key_hints::key_names, ClearState, GameOverState, LoadState, PauseState},
 };
 
 fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
@@ -43,17 +54,23 @@ fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
     )
 }
 
+/// a camera on the whole stage, or on the player with `Scrolling` if the stage doesn't fit
 fn create_camera(world: &mut World, map_size: (i32, i32)) {
     let mut transform = Transform::default();
-    let map_size = (map_size.0 as f32, map_size.1 as f32);
+    let (height, width) = (map_size.0 as f32, map_size.1 as f32);
 
-    let view_size = (map_size.0.max(map_size.1).max(DEFAULT_GRID_SIZE) + 4.) * CELL_SIZE;
-    transform.set_translation_xyz(map_size.1 * CELL_SIZE * 0.5, map_size.0 * CELL_SIZE * 0.5, 10.);
+    let fitting_size = height.max(width).max(DEFAULT_GRID_SIZE) + 4.;
+    let view_size = fitting_size.min(MAX_VIEW_SIZE);
+    transform.set_translation_xyz(width * CELL_SIZE * 0.5, height * CELL_SIZE * 0.5, 10.);
 
-    world.create_entity()
+    let camera = world.create_entity()
         .with(transform)
-        .with(Camera::standard_2d(view_size, view_size))
-        .build();
+        .with(Camera::standard_2d(view_size * CELL_SIZE, view_size * CELL_SIZE));
+    if fitting_size > view_size {
+        camera.with(Scrolling { map_size, view_size }).build();
+    } else {
+        camera.build();
+    }
 }
 
 fn create_player(
@@ -69,6 +86,7 @@ fn create_player(
         .with(grid.to_transform(2.))
         .with(grid)
         .with(Player)
+        .with(Inventory::default())
         .build();
 }
 
@@ -89,6 +107,57 @@ fn create_wall(
         .build();
 }
 
+/// keys and doors with the same letter, and teleporters with the same digit, share a color
+fn pair_tint(index: usize) -> Tint {
+    const COLORS: [(f32, f32, f32); 6] = [
+        (1., 0.85, 0.3),
+        (0.4, 0.8, 1.),
+        (1., 0.45, 0.45),
+        (0.5, 1., 0.5),
+        (0.85, 0.55, 1.),
+        (1., 0.65, 0.3),
+    ];
+    let (r, g, b) = COLORS[index % COLORS.len()];
+    Tint(Srgba::new(r, g, b, 1.))
+}
+
+fn create_key(
+    world: &mut World,
+    (y, x): (i32, i32),
+    letter: char,
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 3);
+    let grid = Grid2D::new(x, y);
+
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(1.))
+        .with(grid)
+        .with(pair_tint(letter as usize - 'a' as usize))
+        .with(Key(letter))
+        .build();
+}
+
+/// `DoorSystem` removes the `Obstacle` and hides the door once its key is collected
+fn create_door(
+    world: &mut World,
+    (y, x): (i32, i32),
+    letter: char,
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 4);
+    let grid = Grid2D::new(x, y);
+
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(1.))
+        .with(grid)
+        .with(pair_tint(letter as usize - 'a' as usize))
+        .with(Door(letter))
+        .with(Obstacle)
+        .build();
+}
 
 fn create_goal(
     world: &mut World,
@@ -106,101 +175,322 @@ fn create_goal(
         .build();
 }
 
-fn create_background(
+fn create_box(
     world: &mut World,
-    (height, width): (i32, i32),
-    sprite_sheet_handle: Handle<SpriteSheet>)
-{
-    let sprite_render = SpriteRender::new(sprite_sheet_handle, 15);
-    for x in 0..width {
-        for y in 0..height {
-            let mut transform: Transform = Grid2D::new(x, y).into();
-            transform.set_translation_z(-10.);
+    (y, x): (i32, i32),
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 5);
+    let grid = Grid2D::new(x, y);
 
-            world.create_entity()
-                .with(sprite_render.clone())
-                .with(transform)
-                .build();
-        }
-    }
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(1.5))
+        .with(grid)
+        .with(Pushable)
+        .build();
+}
 
-    // top and bottom
-    for x in 0..width {
-        let grid_bottom = Grid2D::new(x, -1);
+fn create_pad(
+    world: &mut World,
+    (y, x): (i32, i32),
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 6);
+    let grid = Grid2D::new(x, y);
 
-        world.create_entity()
-            .with(Transform::from(grid_bottom.clone()))
-            .with(grid_bottom)
-            .with(WallInvisible)
-            .with(Obstacle)
-            .build();
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(0.5))
+        .with(grid)
+        .with(Pad)
+        .build();
+}
 
-        let grid_top = Grid2D::new(x, height);
+fn create_ice(
+    world: &mut World,
+    (y, x): (i32, i32),
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 7);
+    let grid = Grid2D::new(x, y);
 
-        world.create_entity()
-            .with(Transform::from(grid_top.clone()))
-            .with(grid_top)
-            .with(WallInvisible)
-            .with(Obstacle)
-            .build();
-    }
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(0.5))
+        .with(grid)
+        .with(Ice)
+        .build();
+}
 
-    for y in 0..height {
-        let grid_left = Grid2D::new(-1, y);
+fn create_teleporter(
+    world: &mut World,
+    (y, x): (i32, i32),
+    digit: char,
+    (partner_y, partner_x): (i32, i32),
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 8);
+    let grid = Grid2D::new(x, y);
 
-        world.create_entity()
-            .with(Transform::from(grid_left.clone()))
-            .with(grid_left)
-            .with(WallInvisible)
-            .with(Obstacle)
-            .build();
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(0.5))
+        .with(grid)
+        .with(pair_tint(digit as usize - '1' as usize))
+        .with(Teleporter { partner: Grid2D::new(partner_x, partner_y) })
+        .build();
+}
+
+fn create_arrow(
+    world: &mut World,
+    (y, x): (i32, i32),
+    direction: Direction,
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    // the sprite points up
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 9);
+    let grid = Grid2D::new(x, y);
+    let mut transform = grid.to_transform(0.5);
+    let angle = match direction {
+        Direction::Up => 0.,
+        Direction::Left => 0.5,
+        Direction::Down => 1.,
+        Direction::Right => 1.5,
+    };
+    transform.set_rotation_2d(angle * std::f32::consts::PI);
 
-        let grid_right = Grid2D::new(width, y);
+    world.create_entity()
+        .with(sprite_render)
+        .with(transform)
+        .with(grid)
+        .with(OneWay(direction))
+        .build();
+}
 
-        world.create_entity()
-            .with(Transform::from(grid_right.clone()))
-            .with(grid_right)
-            .with(WallInvisible)
-            .with(Obstacle)
-            .build();
+fn create_enemy(
+    world: &mut World,
+    patrol: &[(i32, i32)],
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 10);
+    let enemy = Enemy::new(patrol.iter().map(|&(y, x)| Grid2D::new(x, y)).collect());
+    let grid = enemy.position();
+
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(1.8))
+        .with(grid)
+        .with(Tint(Srgba::new(1., 0.35, 0.35, 1.)))
+        .with(enemy)
+        .build();
+}
+
+fn create_hunter(
+    world: &mut World,
+    (y, x): (i32, i32),
+    hunter: Hunter,
+    sprite_sheet_handle: Handle<SpriteSheet>
+) {
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 10);
+    let grid = Grid2D::new(x, y);
+
+    world.create_entity()
+        .with(sprite_render)
+        .with(grid.to_transform(1.8))
+        .with(grid)
+        .with(Tint(Srgba::new(0.75, 0.4, 1., 1.)))
+        .with(hunter)
+        .build();
+}
+
+fn create_background(
+    world: &mut World,
+    (height, width): (i32, i32),
+    sprite_sheet_handle: Handle<SpriteSheet>)
+{
+    // the tiles cover the view of a scrolling camera from one cell further on every side
+    let tiles = MAX_VIEW_SIZE as i32 + 2;
+    let size = (height.min(tiles), width.min(tiles));
+    let mut root = Transform::default();
+    root.set_translation_z(-10.);
+    let root = world.create_entity()
+        .with(root)
+        .with(Background { size })
+        .build();
+
+    let sprite_render = SpriteRender::new(sprite_sheet_handle, 15);
+    for x in 0..size.1 {
+        for y in 0..size.0 {
+            world.create_entity()
+                .with(sprite_render.clone())
+                .with(Transform::from(Grid2D::new(x, y)))
+                .with(Parent { entity: root })
+                .build();
+        }
     }
 }
 
-fn load_map(world: &mut World) -> Vec<Vec<char>> {
-    let current_stage_data = world.try_fetch::<CurrentStageData>().unwrap();
-    let stage = current_stage_data.stage;
+fn load_stage(world: &mut World) -> Result<Stage, StageLoadError> {
+    let stage = world.try_fetch::<CurrentStageData>().unwrap().stage;
+    let manifest = world.read_resource::<StageManifest>();
+    let entry = manifest.get(stage).ok_or(StageLoadError::NotInManifest(stage))?;
 
-    let map = std::fs::read_to_string(format!("./resources/stages/{:02}.txt", stage))
-        .unwrap_or("@............\n.###.#.#.##..\n.#...#.#.#.#.\n.###.#.#.#.#.\n.#...#.#.#.#.\n.###.###.##..\n............G".to_string())
-        .trim()
-        .lines()
-        .map(|s| s.chars().collect())
-        .collect();
-    map
+    let stage = manifest.load_stage(entry)?;
+    let optimal_moves = world.write_resource::<Solutions>().optimal_moves(&entry.id, &stage);
+    world.write_resource::<StageStats>().optimal_moves = optimal_moves;
+    Ok(stage)
 }
 
 fn prepare_stage(
     world: &mut World,
+    stage: &Stage,
     sprite_sheet_handle: Handle<SpriteSheet>
 ) {
-    let stage_map = load_map(world);
-    let height = stage_map.len() as i32;
-    let width = stage_map[0].len() as i32;
-
+    let (height, width) = stage.size();
+
+    // before the entities, which it indexes from their insertion events on
+    let occupancy = Occupancy::new(&world.entities(), &mut world.write_storage(), (height, width));
+    world.insert(occupancy);
+    let cells = |positions: Vec<(i32, i32)>| positions.into_iter().map(|(y, x)| Grid2D::new(x, y)).collect();
+    world.insert(StageTargets {
+        goals: cells(stage.positions_of(|tile| tile == Tile::Goal)),
+        pads: cells(stage.positions_of(Tile::is_pad)),
+    });
     create_camera(world, (height, width));
     create_background(world, (height, width), sprite_sheet_handle.clone());
-    for (i, row) in stage_map.iter().enumerate() {
-        let i = i as i32;
-        for (j, &c) in row.iter().enumerate() {
-            let j = j as i32;
-            match c {
-                '#' => create_wall(world, (i, j), sprite_sheet_handle.clone()),
-                '@' => create_player(world, (i, j), sprite_sheet_handle.clone()),
-                'G' => create_goal(world, (i, j), sprite_sheet_handle.clone()),
-                '.' => (),
-                _ => unreachable!(),
-            };
-        }
+    for (position, tile) in stage.cells() {
+        match tile {
+            Tile::Wall => create_wall(world, position, sprite_sheet_handle.clone()),
+            Tile::Player => create_player(world, position, sprite_sheet_handle.clone()),
+            Tile::Goal => create_goal(world, position, sprite_sheet_handle.clone()),
+            Tile::Key(letter) => create_key(world, position, letter, sprite_sheet_handle.clone()),
+            Tile::Door(letter) => create_door(world, position, letter, sprite_sheet_handle.clone()),
+            Tile::Box => create_box(world, position, sprite_sheet_handle.clone()),
+            Tile::Pad => create_pad(world, position, sprite_sheet_handle.clone()),
+            Tile::Ice => create_ice(world, position, sprite_sheet_handle.clone()),
+            Tile::Arrow(direction) => create_arrow(world, position, direction, sprite_sheet_handle.clone()),
+            Tile::Teleporter(digit) => {
+                // the parser checked that every digit comes in pairs
+                let partner = stage.teleporter_partner(position).unwrap();
+                create_teleporter(world, position, digit, partner, sprite_sheet_handle.clone());
+            }
+            Tile::BoxOnPad => {
+                create_pad(world, position, sprite_sheet_handle.clone());
+                create_box(world, position, sprite_sheet_handle.clone());
+            }
+            Tile::Empty => (),
+        };
+    }
+    for patrol in stage.patrols() {
+        create_enemy(world, patrol, sprite_sheet_handle.clone());
+    }
+    for hunter in stage.hunters() {
+        let component = Hunter { speed: hunter.speed, vision: hunter.vision };
+        create_hunter(world, hunter.start, component, sprite_sheet_handle.clone());
+    }
+}
+
+/// Create the entities of `stage` in `world`, with the sprites of the sprite sheet
+/// the `Loader` of the world finds under `sprites/`.
+/// It needs no window, the sprite sheet is only drawn once a renderer processed it.
+pub fn spawn_stage(world: &mut World, stage: &Stage) {
+    let sprite_sheet_handle = load_sprite_sheet(world);
+    prepare_stage(world, stage, sprite_sheet_handle);
+}
+
+/// stage name on the top left, counters on the top right, filled in by `HudSystem`
+fn create_hud(world: &mut World) {
+    let font_handle: Handle<FontAsset> = world.read_resource::<Loader>().load(
+        "fonts/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource(),
+    );
+
+    let stage = world.try_fetch::<CurrentStageData>().unwrap().stage;
+    let name = world.read_resource::<StageManifest>().get(stage).map(|entry| entry.name.clone()).unwrap_or_default();
+
+    world
+        .create_entity()
+        .with(UiTransform::new(
+            "hud stage".to_string(), Anchor::TopLeft, Anchor::TopLeft,
+            8., -8., 5., 300., 20.,
+        ))
+        .with(UiText::new(
+            font_handle.clone(),
+            format!("{:02} {}", stage + 1, name),
+            [1., 1., 1., 1.],
+            16.,
+            LineMode::Single,
+            Anchor::TopLeft,
+        ))
+        .with(HudField::StageName)
+        .build();
+
+    let fields = [HudField::Moves, HudField::Bumps, HudField::Time, HudField::Keys];
+    for (i, field) in fields.iter().enumerate() {
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                format!("hud {:?}", field), Anchor::TopRight, Anchor::TopRight,
+                -8., -8. - 20. * i as f32, 5., 160., 20.,
+            ))
+            .with(UiText::new(
+                font_handle.clone(),
+                String::new(),
+                [1., 1., 1., 1.],
+                16.,
+                LineMode::Single,
+                Anchor::TopRight,
+            ))
+            .with(*field)
+            .build();
+    }
+}
+
+/// show why the stage could not be loaded instead of the maze
+fn create_error_window(world: &mut World, error: &StageLoadError) {
+    let font_handle: Handle<FontAsset> = world.read_resource::<Loader>().load(
+        "fonts/square.ttf",
+        TtfFormat,
+        (),
+        &world.read_resource(),
+    );
+
+    let stage = world.try_fetch::<CurrentStageData>().unwrap().stage;
+    let name = world.read_resource::<StageManifest>()
+        .get(stage)
+        .map(|entry| entry.name.clone())
+        .unwrap_or_else(|| format!("stage {}", stage + 1));
+    let lines = [
+        (format!("{} is broken", name), 30., 40.),
+        (error.to_string(), 16., 0.),
+        (
+            format!(
+                "press {} to retry or {} for title",
+                key_names(world, actions::RESTART), key_names(world, actions::TITLE)
+            ),
+            20., -40.,
+        ),
+    ];
+
+    for (i, (message, font_size, y)) in lines.iter().enumerate() {
+        world
+            .create_entity()
+            .with(UiTransform::new(
+                format!("error{}", i), Anchor::Middle, Anchor::Middle,
+                0., *y, 9.5, 500., 40.,
+            ))
+            .with(UiText::new(
+                font_handle.clone(),
+                message.clone(),
+                [1., 1., 1., 1.],
+                *font_size,
+                LineMode::Single,
+                Anchor::Middle,
+            ))
+            .build();
     }
 }
 
@@ -211,14 +501,23 @@ impl SimpleState for PlayState {
         let world = data.world;
       
         world.delete_all();
-        let sprite_sheet_handle = load_sprite_sheet(world);
-        prepare_stage(world, sprite_sheet_handle);
+        world.insert(MoveHistory::default());
+        world.insert(StageStats::default());
+        match load_stage(world) {
+            Ok(stage) => {
+                spawn_stage(world, &stage);
+                create_hud(world);
+            }
+            Err(error) => create_error_window(world, &error),
+        }
     }
 
     fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
         if let Some(stage_data) = data.world.try_fetch::<CurrentStageData>() {
-            if stage_data.state == GameState::Win {
-                return Trans::Switch(Box::new(ClearState));
+            match stage_data.state {
+                GameState::Win => return Trans::Switch(Box::new(ClearState)),
+                GameState::Lose => return Trans::Switch(Box::new(GameOverState)),
+                _ => (),
             }
         }
         Trans::None
@@ -226,14 +525,25 @@ impl SimpleState for PlayState {
 
     fn handle_event(
         &mut self,
-        _data: StateData<'_, GameData<'_, '_>>,
+        data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent
     ) -> SimpleTrans {
-        if let StateEvent::Window(ref event) = event {
-            if is_close_requested(event) || is_key_down(event, VirtualKeyCode::Escape) {
-                return Trans::Quit
-            }
+        match event {
+            StateEvent::Window(ref event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(ref action)) => match action.as_str() {
+                // a stage won or lost in this frame moves on in `update`, pausing would undo that
+                actions::PAUSE if data.world.read_resource::<CurrentStageData>().state == GameState::Play => {
+                    Trans::Push(Box::<PauseState>::default())
+                }
+                actions::RESTART => {
+                    data.world.try_fetch_mut::<CurrentStageData>().unwrap().restart();
+                    Trans::Switch(Box::new(PlayState))
+                }
+                // LoadState puts a fresh CurrentStageData back
+                actions::TITLE => Trans::Switch(Box::new(LoadState)),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
         }
-        Trans::None
     }
 }
\ No newline at end of file
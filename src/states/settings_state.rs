@@ -0,0 +1,172 @@
+use amethyst::{
+    prelude::*,
+    assets::{Handle, Loader},
+    ecs::Entity,
+    input::{is_close_requested, InputEvent},
+    renderer::palette::{Pixel, Srgba},
+    ui::{Anchor, FontAsset, LineMode, TtfFormat, UiImage, UiText, UiTransform},
+};
+
+use crate::{
+    config::actions,
+    resources::SaveData,
+};
+
+const SELECTED_COLOR: [f32; 4] = [1., 0.8, 0.2, 1.];
+const ENABLED_COLOR: [f32; 4] = [1., 1., 1., 1.];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SettingsItem {
+    Hud,
+    Animations,
+    Back,
+}
+
+impl SettingsItem {
+    const ALL: [SettingsItem; 3] = [SettingsItem::Hud, SettingsItem::Animations, SettingsItem::Back];
+
+    fn label(self, save_data: &SaveData) -> String {
+        let on_off = |value| if value { "on" } else { "off" };
+        match self {
+            SettingsItem::Hud => format!("HUD: {}", on_off(save_data.settings.show_hud)),
+            SettingsItem::Animations => format!("Animations: {}", on_off(save_data.settings.animations)),
+            SettingsItem::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// Pushed on top of `PauseState`, edits `SaveData::settings` and stores them when closed.
+#[derive(Default)]
+pub struct SettingsState {
+    selected: usize,
+    /// background, title, then one text per `SettingsItem::ALL`
+    entities: Vec<Entity>,
+}
+
+impl SettingsState {
+    fn update_texts(&self, world: &mut World) {
+        let save_data = world.read_resource::<SaveData>();
+        let mut texts = world.write_storage::<UiText>();
+        for (i, (item, entity)) in SettingsItem::ALL.iter().zip(&self.entities[2..]).enumerate() {
+            if let Some(text) = texts.get_mut(*entity) {
+                text.text = item.label(&save_data);
+                text.color = if i == self.selected { SELECTED_COLOR } else { ENABLED_COLOR };
+            }
+        }
+    }
+
+    fn create_menu(&mut self, world: &mut World) {
+        let font_handle: Handle<FontAsset> = world.read_resource::<Loader>().load(
+            "fonts/square.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+
+        let background = world.create_entity()
+            .with(UiTransform::new(
+                "settings background".to_string(), Anchor::Middle, Anchor::Middle,
+                0., 0., 9., 550., 550.,
+            ))
+            .with(UiImage::SolidColor(
+                Srgba::new(0.05, 0.02, 0.06, 0.8)
+                    .into_linear()
+                    .into_raw()
+            ))
+            .build();
+        self.entities.push(background);
+
+        let title = world
+            .create_entity()
+            .with(UiTransform::new(
+                "settings title".to_string(), Anchor::Middle, Anchor::Middle,
+                0., 120., 9.5, 500., 60.,
+            ))
+            .with(UiText::new(
+                font_handle.clone(),
+                "Settings".to_string(),
+                [1., 1., 1., 1.],
+                40.,
+                LineMode::Single,
+                Anchor::Middle,
+            ))
+            .build();
+        self.entities.push(title);
+
+        for (i, item) in SettingsItem::ALL.iter().enumerate() {
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("{:?}", item), Anchor::Middle, Anchor::Middle,
+                    0., 50. - 40. * i as f32, 9.5, 500., 40.,
+                ))
+                .with(UiText::new(
+                    font_handle.clone(),
+                    String::new(),
+                    ENABLED_COLOR,
+                    24.,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build();
+            self.entities.push(entity);
+        }
+        self.update_texts(world);
+    }
+
+    fn confirm(&mut self, world: &mut World) -> SimpleTrans {
+        {
+            let mut save_data = world.write_resource::<SaveData>();
+            match SettingsItem::ALL[self.selected] {
+                SettingsItem::Hud => save_data.settings.show_hud = !save_data.settings.show_hud,
+                SettingsItem::Animations => save_data.settings.animations = !save_data.settings.animations,
+                SettingsItem::Back => return Trans::Pop,
+            }
+        }
+        self.update_texts(world);
+        Trans::None
+    }
+}
+
+impl SimpleState for SettingsState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        self.create_menu(data.world);
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.delete_entities(&self.entities).expect("failed to remove the settings menu");
+        self.entities.clear();
+
+        if let Err(e) = world.read_resource::<SaveData>().store() {
+            log::warn!("failed to save settings: {}", e);
+        }
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent
+    ) -> SimpleTrans {
+        let len = SettingsItem::ALL.len();
+        match event {
+            StateEvent::Window(ref event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(ref action)) => match action.as_str() {
+                actions::PAUSE => Trans::Pop,
+                actions::UP => {
+                    self.selected = (self.selected + len - 1) % len;
+                    self.update_texts(data.world);
+                    Trans::None
+                }
+                actions::DOWN => {
+                    self.selected = (self.selected + 1) % len;
+                    self.update_texts(data.world);
+                    Trans::None
+                }
+                actions::CONFIRM => self.confirm(data.world),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use amethyst::{
+    prelude::*,
+    assets::{Handle, Loader},
+    ecs::Entity,
+    input::{is_close_requested, InputEvent},
+    renderer::palette::{Pixel, Srgba},
+    ui::{Anchor, FontAsset, LineMode, TtfFormat, UiImage, UiText, UiTransform},
+};
+
+use crate::{
+    config::actions,
+    resources::{CurrentStageData, SaveData, StageManifest, StagePreviews},
+    states::{key_hints::key_names, LoadState, PlayState},
+};
+
+const COLUMNS: usize = 4;
+const ROWS: usize = 3;
+const TILE_WIDTH: f32 = 110.;
+const TILE_HEIGHT: f32 = 120.;
+const TILE_SPACING: f32 = 8.;
+/// the size preview of the largest side of a stage
+const PREVIEW_SIZE: f32 = 48.;
+
+const SELECTED_COLOR: [f32; 4] = [1., 0.8, 0.2, 1.];
+const TEXT_COLOR: [f32; 4] = [1., 1., 1., 1.];
+const LOCKED_TEXT_COLOR: [f32; 4] = [0.4, 0.4, 0.4, 1.];
+const CLEARED_COLOR: [f32; 4] = [0.4, 0.9, 0.4, 1.];
+
+fn solid(r: f32, g: f32, b: f32, a: f32) -> UiImage {
+    UiImage::SolidColor(Srgba::new(r, g, b, a).into_linear().into_raw())
+}
+
+/// what a tile shows about a stage of the manifest
+struct StageTile {
+    label: String,
+    /// `None` if the stage could not be loaded
+    size: Option<(i32, i32)>,
+    cleared: bool,
+    unlocked: bool,
+}
+
+fn read_tiles(world: &World) -> Vec<StageTile> {
+    let manifest = world.read_resource::<StageManifest>();
+    let save_data = world.read_resource::<SaveData>();
+    let mut previews = world.write_resource::<StagePreviews>();
+    (0..manifest.len())
+        .map(|stage| {
+            let entry = manifest.get(stage).unwrap();
+            StageTile {
+                label: format!("{:02} {}", stage + 1, entry.name),
+                size: previews.size(&manifest, entry),
+                cleared: save_data.records.contains_key(&entry.id),
+                unlocked: save_data.is_unlocked(&manifest, stage),
+            }
+        })
+        .collect()
+}
+
+/// Grid of the stages of the manifest, opened from the title screen and the pause menu.
+/// Locked stages are shown but can't be started.
+#[derive(Default)]
+pub struct StageSelectState {
+    tiles: Vec<StageTile>,
+    selected: usize,
+    /// the first tile shown, the grid scrolls by pages of `COLUMNS * ROWS` tiles
+    page_start: usize,
+    /// entities of the shown page, rebuilt whenever the page or the selection changes
+    entities: Vec<Entity>,
+}
+
+impl StageSelectState {
+    fn create_page(&mut self, world: &mut World) {
+        world.delete_entities(&self.entities).expect("failed to remove the stage tiles");
+        self.entities.clear();
+
+        let font_handle: Handle<FontAsset> = world.read_resource::<Loader>().load(
+            "fonts/square.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+
+        let page_len = COLUMNS * ROWS;
+        self.page_start = self.selected / page_len * page_len;
+        let page_end = (self.page_start + page_len).min(self.tiles.len());
+        for index in self.page_start..page_end {
+            let i = index - self.page_start;
+            let x = (i % COLUMNS) as f32 - (COLUMNS as f32 - 1.) * 0.5;
+            let y = (ROWS as f32 - 1.) * 0.5 - (i / COLUMNS) as f32;
+            let center = (
+                x * (TILE_WIDTH + TILE_SPACING),
+                y * (TILE_HEIGHT + TILE_SPACING) - 20.,
+            );
+            self.create_tile(world, index, center, font_handle.clone());
+        }
+
+        let pages = self.tiles.len().div_ceil(page_len);
+        let keys = format!(
+            "{}: play    {}: title",
+            key_names(world, actions::CONFIRM), key_names(world, actions::PAUSE)
+        );
+        let footer = if pages > 1 {
+            format!("page {}/{}    {}", self.page_start / page_len + 1, pages, keys)
+        } else {
+            keys
+        };
+        for (text, font_size, y) in [("Stage Select".to_string(), 36., 220.), (footer, 16., -232.)] {
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    text.clone(), Anchor::Middle, Anchor::Middle,
+                    0., y, 9.5, 500., 40.,
+                ))
+                .with(UiText::new(
+                    font_handle.clone(),
+                    text,
+                    TEXT_COLOR,
+                    font_size,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build();
+            self.entities.push(entity);
+        }
+    }
+
+    fn create_tile(&mut self, world: &mut World, index: usize, (x, y): (f32, f32), font_handle: Handle<FontAsset>) {
+        let tile = &self.tiles[index];
+        let mut entities = Vec::new();
+        let mut panel = |name: String, (dx, dy): (f32, f32), z: f32, (width, height): (f32, f32), image: UiImage| {
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("stage{} {}", index, name), Anchor::Middle, Anchor::Middle,
+                    x + dx, y + dy, z, width, height,
+                ))
+                .with(image)
+                .build();
+            entities.push(entity);
+        };
+
+        if index == self.selected {
+            panel("frame".to_string(), (0., 0.), 8.5, (TILE_WIDTH + 6., TILE_HEIGHT + 6.), solid(1., 0.8, 0.2, 1.));
+        }
+        let background = if tile.unlocked { solid(0.12, 0.1, 0.16, 1.) } else { solid(0.06, 0.05, 0.08, 1.) };
+        panel("background".to_string(), (0., 0.), 9., (TILE_WIDTH, TILE_HEIGHT), background);
+
+        if !tile.unlocked {
+            // lock icon: shackle with its hole, then the body over its lower half
+            panel("shackle".to_string(), (0., 12.), 9.2, (20., 22.), solid(0.5, 0.5, 0.55, 1.));
+            panel("shackle hole".to_string(), (0., 14.), 9.3, (12., 18.), solid(0.06, 0.05, 0.08, 1.));
+            panel("lock".to_string(), (0., -4.), 9.4, (30., 22.), solid(0.5, 0.5, 0.55, 1.));
+        } else if let Some((height, width)) = tile.size {
+            // the stage outline at the scale of its largest side
+            let scale = PREVIEW_SIZE / height.max(width) as f32;
+            let size = (width as f32 * scale, height as f32 * scale);
+            panel("preview".to_string(), (0., 4.), 9.2, size, solid(0.17, 0.15, 0.22, 1.));
+        }
+
+        let color = if tile.unlocked { TEXT_COLOR } else { LOCKED_TEXT_COLOR };
+        let size = match tile.size {
+            Some((height, width)) => format!("{}x{}", width, height),
+            None => "broken".to_string(),
+        };
+        let mut lines = vec![(tile.label.clone(), color, 48.), (size, color, -32.)];
+        if tile.cleared {
+            lines.push(("cleared".to_string(), CLEARED_COLOR, -48.));
+        }
+        for (i, (text, color, dy)) in lines.into_iter().enumerate() {
+            let entity = world
+                .create_entity()
+                .with(UiTransform::new(
+                    format!("stage{} text{}", index, i), Anchor::Middle, Anchor::Middle,
+                    x, y + dy, 9.5, TILE_WIDTH - 4., 16.,
+                ))
+                .with(UiText::new(
+                    font_handle.clone(),
+                    text,
+                    if index == self.selected && i == 0 { SELECTED_COLOR } else { color },
+                    12.,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build();
+            entities.push(entity);
+        }
+        self.entities.extend(entities);
+    }
+
+    fn move_cursor(&mut self, world: &mut World, step: isize) {
+        let selected = self.selected as isize + step;
+        if selected >= 0 && (selected as usize) < self.tiles.len() {
+            self.selected = selected as usize;
+            self.create_page(world);
+        }
+    }
+
+    fn confirm(&self, world: &mut World) -> SimpleTrans {
+        if !self.tiles[self.selected].unlocked {
+            return Trans::None;
+        }
+        world.insert(CurrentStageData { stage: self.selected, ..Default::default() });
+        Trans::Switch(Box::new(PlayState))
+    }
+}
+
+impl SimpleState for StageSelectState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+        world.delete_all();
+
+        self.tiles = read_tiles(world);
+        self.selected = world.try_fetch::<CurrentStageData>()
+            .map_or(0, |stage_data| stage_data.stage)
+            .min(self.tiles.len() - 1);
+        self.create_page(world);
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent
+    ) -> SimpleTrans {
+        match event {
+            StateEvent::Window(ref event) if is_close_requested(event) => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(ref action)) => {
+                let step = match action.as_str() {
+                    actions::PAUSE => return Trans::Switch(Box::new(LoadState)),
+                    actions::CONFIRM => return self.confirm(data.world),
+                    actions::UP => -(COLUMNS as isize),
+                    actions::DOWN => COLUMNS as isize,
+                    actions::LEFT => -1,
+                    actions::RIGHT => 1,
+                    _ => return Trans::None,
+                };
+                self.move_cursor(data.world, step);
+                Trans::None
+            }
+            _ => Trans::None,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::{Join, ReadStorage, System, SystemData, WriteStorage},
+};
+
+use crate::{
+    components::{
+        basics::Player,
+        camera::{Background, Scrolling},
+    },
+    config::CELL_SIZE,
+};
+
+/// Keep a `Scrolling` camera on the sprite of the player, showing at most two cells
+/// beyond the edges of the stage like a camera on the whole stage does,
+/// and the `Background` tiles under it.
+#[derive(Default, SystemDesc)]
+pub struct CameraSystem;
+
+impl<'s> System<'s> for CameraSystem {
+    type SystemData = (
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Scrolling>,
+        ReadStorage<'s, Background>,
+        WriteStorage<'s, Transform>,
+    );
+
+    fn run(&mut self, (player, scrollings, backgrounds, mut transforms): Self::SystemData) {
+        let (player_x, player_y) = match (&player, &transforms).join().next() {
+            Some((_, transform)) => (transform.translation().x, transform.translation().y),
+            None => return
+        };
+
+        let (center, (height, width)) = match (&scrollings, &mut transforms).join().next() {
+            Some((scrolling, transform)) => {
+                let half_view = scrolling.view_size * 0.5 - 2.;
+                let (height, width) = scrolling.map_size;
+                // a side the view covers whole stays centered, like on a camera which doesn't scroll
+                let follow = |position: f32, cells: i32| {
+                    let cells = cells as f32;
+                    if cells <= scrolling.view_size - 4. {
+                        cells * CELL_SIZE / 2.
+                    } else {
+                        position.max(half_view * CELL_SIZE).min((cells - half_view) * CELL_SIZE)
+                    }
+                };
+                let center = (follow(player_x, width), follow(player_y, height));
+                transform.set_translation_x(center.0);
+                transform.set_translation_y(center.1);
+                (center, scrolling.map_size)
+            }
+            None => return
+        };
+
+        // by whole cells, so that the tiles stay in line with the stage
+        for (background, transform) in (&backgrounds, &mut transforms).join() {
+            let (tiles_height, tiles_width) = background.size;
+            let origin = |position: f32, tiles: i32, cells: i32| {
+                let cell = (position / CELL_SIZE).floor() as i32 - tiles / 2;
+                cell.clamp(0, cells - tiles) as f32 * CELL_SIZE
+            };
+            transform.set_translation_x(origin(center.0, tiles_width, width));
+            transform.set_translation_y(origin(center.1, tiles_height, height));
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use amethyst::{
+    core::Hidden,
+    derive::SystemDesc,
+    ecs::{Entities, Join, ReadStorage, System, SystemData, WriteStorage},
+};
+
+use crate::components::{
+    basics::{Door, Obstacle, Player},
+    inventory::Inventory,
+};
+
+/// Open the doors whose key is in the player's inventory, and close them again
+/// when the pickup is undone.
+#[derive(Default, SystemDesc)]
+pub struct DoorSystem;
+
+impl<'s> System<'s> for DoorSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Inventory>,
+        ReadStorage<'s, Door>,
+        WriteStorage<'s, Obstacle>,
+        WriteStorage<'s, Hidden>,
+    );
+
+    fn run(&mut self, (entities, player, inventories, doors, mut obstacles, mut hiddens): Self::SystemData) {
+        let inventory = match (&player, &inventories).join().next() {
+            Some((_, inventory)) => inventory,
+            None => return
+        };
+
+        for (entity, door) in (&entities, &doors).join() {
+            let open = inventory.has(door.0);
+            if open && obstacles.contains(entity) {
+                obstacles.remove(entity);
+                hiddens.insert(entity, Hidden).expect("door entity is alive");
+            } else if !open && !obstacles.contains(entity) {
+                obstacles.insert(entity, Obstacle).expect("door entity is alive");
+                hiddens.remove(entity);
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use amethyst::{
+    core::Transform,
+    derive::SystemDesc,
+    ecs::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
+};
+
+use crate::{
+    components::{
+        basics::{Door, Obstacle, Player, Pushable},
+        enemy::{Enemy, Hunter},
+        grid2d::{Direction, Grid2D},
+        inventory::Inventory,
+        tween::Tween,
+    },
+    config::{MOVE_DURATION, MOVE_EASING},
+    resources::{Change, CurrentStageData, GameState, MoveHistory, Occupancy, SaveData},
+    rules::{hunt, is_caught, Board},
+};
+
+/// `Board` of the cells of `Occupancy` as hunters see them. They go around walls, boxes
+/// and the doors whose key the player doesn't hold yet, without waiting for `DoorSystem`
+/// to open them, like the solver expects.
+struct HunterBoard<'a> {
+    occupancy: &'a Occupancy,
+    obstacles: &'a ReadStorage<'a, Obstacle>,
+    doors: &'a ReadStorage<'a, Door>,
+    pushables: &'a ReadStorage<'a, Pushable>,
+    inventory: &'a Inventory,
+}
+
+impl Board<Grid2D> for HunterBoard<'_> {
+    fn is_blocked(&self, cell: &Grid2D) -> bool {
+        !self.occupancy.contains(cell) || self.occupancy.at(cell).iter().any(|&entity| match self.doors.get(entity) {
+            Some(door) => !self.inventory.has(door.0),
+            None => self.obstacles.contains(entity),
+        })
+    }
+
+    fn has_box(&self, cell: &Grid2D) -> bool {
+        self.occupancy.find(cell, self.pushables).is_some()
+    }
+
+    fn has_key(&self, _: &Grid2D) -> bool {
+        false
+    }
+
+    fn is_ice(&self, _: &Grid2D) -> bool {
+        false
+    }
+
+    fn allows(&self, _: &Grid2D, _: Direction) -> bool {
+        true
+    }
+
+    fn teleporter_partner(&self, _: &Grid2D) -> Option<Grid2D> {
+        None
+    }
+}
+
+/// Move every enemy one step along its patrol, and every hunter towards the player,
+/// after each player move, as part of the same turn of `MoveHistory`, and lose the stage on contact.
+#[derive(Default, SystemDesc)]
+pub struct EnemySystem;
+
+impl<'s> System<'s> for EnemySystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Obstacle>,
+        ReadStorage<'s, Door>,
+        ReadStorage<'s, Pushable>,
+        ReadStorage<'s, Inventory>,
+        ReadStorage<'s, Hunter>,
+        WriteStorage<'s, Enemy>,
+        WriteStorage<'s, Grid2D>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Tween>,
+        Write<'s, MoveHistory>,
+        Write<'s, CurrentStageData>,
+        Read<'s, SaveData>,
+        Option<Write<'s, Occupancy>>,
+    );
+
+    fn run(&mut self, (entities, player, obstacles, doors, pushables, inventories, hunters, mut enemies, mut grid2ds, mut transforms, mut tweens, mut history, mut stage_data, save_data, occupancy): Self::SystemData) {
+        if !history.take_new_turn() || stage_data.state != GameState::Play {
+            return;
+        }
+        let mut occupancy = match occupancy {
+            Some(occupancy) => occupancy,
+            None => return
+        };
+        let (player_position, inventory) = match (&player, &grid2ds, &inventories).join().next() {
+            Some((_, grid, inventory)) => (grid.clone(), inventory),
+            None => return
+        };
+
+        let mut changes = Vec::new();
+        let mut caught = false;
+        for (entity, enemy, grid2d) in (&entities, &mut enemies, &mut grid2ds).join() {
+            let from = enemy.step;
+            enemy.step = enemy.next_step();
+            *grid2d = enemy.position();
+            caught |= is_caught(&player_position, &enemy.patrol[from], grid2d);
+            changes.push(Change::Patrolled { entity, from, to: enemy.step });
+
+            if let Some(transform) = transforms.get_mut(entity) {
+                slide(entity, grid2d, transform, &mut tweens, save_data.settings.animations);
+            }
+        }
+
+        occupancy.sync(&entities, &grid2ds);
+        let board = HunterBoard {
+            occupancy: &occupancy,
+            obstacles: &obstacles,
+            doors: &doors,
+            pushables: &pushables,
+            inventory,
+        };
+        for (entity, hunter, grid2d) in (&entities, &hunters, &mut grid2ds).join() {
+            let from = grid2d.clone();
+            *grid2d = hunt(&board, &from, &player_position, hunter.speed, hunter.vision);
+            caught |= is_caught(&player_position, &from, grid2d);
+            if *grid2d == from {
+                continue;
+            }
+            changes.push(Change::Moved { entity, delta: grid2d.clone() - from });
+
+            if let Some(transform) = transforms.get_mut(entity) {
+                slide(entity, grid2d, transform, &mut tweens, save_data.settings.animations);
+            }
+        }
+
+        history.amend(changes);
+        if caught {
+            stage_data.state = GameState::Lose;
+        }
+    }
+}
+
+/// bring the sprite of `entity` onto `grid2d`, with a tween if animations are on
+fn slide(entity: Entity, grid2d: &Grid2D, transform: &mut Transform, tweens: &mut WriteStorage<'_, Tween>, animate: bool) {
+    if animate {
+        let tween = Tween::slide(transform, grid2d.center(), MOVE_DURATION, MOVE_EASING);
+        tweens.insert(entity, tween).expect("enemy entity is alive");
+    } else {
+        grid2d.update_transform(transform);
+    }
+}
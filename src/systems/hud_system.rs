@@ -0,0 +1,59 @@
+use amethyst::{
+    core::{Hidden, Time},
+    derive::SystemDesc,
+    ecs::{Entities, Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
+    ui::UiText,
+};
+
+use crate::{
+    components::{basics::Player, hud::HudField, inventory::Inventory},
+    resources::{CurrentStageData, GameState, SaveData, StageStats},
+};
+
+/// Count the time spent playing and refresh the HUD texts,
+/// which are hidden when the `show_hud` setting is off.
+#[derive(Default, SystemDesc)]
+pub struct HudSystem;
+
+impl<'s> System<'s> for HudSystem {
+    type SystemData = (
+        Entities<'s>,
+        ReadStorage<'s, HudField>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Inventory>,
+        WriteStorage<'s, UiText>,
+        WriteStorage<'s, Hidden>,
+        Read<'s, Time>,
+        Read<'s, CurrentStageData>,
+        Write<'s, StageStats>,
+        Read<'s, SaveData>,
+    );
+
+    fn run(&mut self, (entities, fields, player, inventories, mut texts, mut hiddens, time, stage_data, mut stats, save_data): Self::SystemData) {
+        if stage_data.state == GameState::Play {
+            stats.elapsed += time.delta_seconds();
+        }
+
+        let keys = (&player, &inventories).join().next()
+            .map(|(_, inventory)| inventory.letters())
+            .unwrap_or_default();
+
+        let show_hud = save_data.settings.show_hud;
+        for (entity, field, text) in (&entities, &fields, &mut texts).join() {
+            if show_hud {
+                hiddens.remove(entity);
+            } else if !hiddens.contains(entity) {
+                hiddens.insert(entity, Hidden).expect("HUD entity is alive");
+            }
+
+            text.text = match field {
+                HudField::StageName => continue,
+                HudField::Moves => format!("moves {}", stats.moves),
+                HudField::Bumps => format!("bumps {}", stats.bumps),
+                HudField::Time => stats.elapsed_text(),
+                HudField::Keys if keys.is_empty() => String::new(),
+                HudField::Keys => format!("keys {}", keys.iter().collect::<String>()),
+            };
+        }
+    }
+}
@@ -1,5 +1,17 @@
+mod camera_system;
+mod door_system;
+mod enemy_system;
+mod hud_system;
 mod player_move_system;
+mod tween_system;
+mod undo_system;
 mod win_system;
 
+pub use camera_system::CameraSystem;
+pub use door_system::DoorSystem;
+pub use enemy_system::EnemySystem;
+pub use hud_system::HudSystem;
 pub use player_move_system::PlayerMoveSystem;
+pub use tween_system::TweenSystem;
+pub use undo_system::UndoSystem;
 pub use win_system::WinSystem;
\ No newline at end of file
@@ -1,18 +1,90 @@
 use amethyst::{
-    core::Transform,
+    core::{Hidden, Transform},
     derive::SystemDesc,
-    ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage},
-    input::{InputHandler, StringBindings, VirtualKeyCode},
+    ecs::{Entities, Entity, Join, Read, ReadStorage, System, SystemData, Write, WriteStorage},
+    input::{InputHandler, StringBindings},
 };
 
 use crate::{
     components::{
-        basics::{Player, Obstacle},
-        grid2d::{Grid2D, Grid2DDelta},
+        basics::{Ice, Key, OneWay, Player, Obstacle, Pushable, Teleporter},
+        grid2d::{Direction, Grid2D},
+        inventory::Inventory,
+        tween::Tween,
     },
-    config::FREEZE_TIME,
+    config::{
+        actions, BUMP_DISTANCE, BUMP_DURATION, BUMP_EASING, CELL_SIZE, FREEZE_TIME,
+        MOVE_DURATION, MOVE_EASING, TELEPORT_DURATION, TELEPORT_EASING,
+    },
+    resources::{Change, CurrentStageData, GameState, MoveHistory, Occupancy, SaveData, StageStats},
+    rules::{plan_move, Board},
 };
 
+/// `Board` of the cells of `Occupancy`, asking the storages what stands on them
+struct OccupiedBoard<'a> {
+    occupancy: &'a Occupancy,
+    obstacles: &'a ReadStorage<'a, Obstacle>,
+    pushables: &'a ReadStorage<'a, Pushable>,
+    keys: &'a ReadStorage<'a, Key>,
+    ices: &'a ReadStorage<'a, Ice>,
+    teleporters: &'a ReadStorage<'a, Teleporter>,
+    one_ways: &'a ReadStorage<'a, OneWay>,
+}
+
+impl Board<Grid2D> for OccupiedBoard<'_> {
+    fn is_blocked(&self, cell: &Grid2D) -> bool {
+        !self.occupancy.contains(cell) || self.occupancy.find(cell, self.obstacles).is_some()
+    }
+
+    fn has_box(&self, cell: &Grid2D) -> bool {
+        self.occupancy.find(cell, self.pushables).is_some()
+    }
+
+    fn has_key(&self, cell: &Grid2D) -> bool {
+        self.occupancy.find(cell, self.keys).is_some()
+    }
+
+    fn is_ice(&self, cell: &Grid2D) -> bool {
+        self.occupancy.find(cell, self.ices).is_some()
+    }
+
+    fn allows(&self, cell: &Grid2D, direction: Direction) -> bool {
+        self.occupancy.find(cell, self.one_ways).is_none_or(|(_, arrow)| arrow.0 == direction)
+    }
+
+    fn teleporter_partner(&self, cell: &Grid2D) -> Option<Grid2D> {
+        self.occupancy.find(cell, self.teleporters).map(|(_, teleporter)| teleporter.partner.clone())
+    }
+}
+
+/// slide `entity` to its new cell `cells` away or teleport it there,
+/// or put it there at once when animations are off
+fn move_sprite(
+    entity: Entity,
+    grid2d: &Grid2D,
+    (cells, teleport): (usize, bool),
+    transforms: &mut WriteStorage<'_, Transform>,
+    tweens: &mut WriteStorage<'_, Tween>,
+    animations: bool,
+) {
+    let transform = match transforms.get_mut(entity) {
+        Some(transform) => transform,
+        None => return
+    };
+    if !animations {
+        grid2d.update_transform(transform);
+        return;
+    }
+    let tween = if teleport {
+        Tween::teleport(transform, grid2d.center(), TELEPORT_DURATION, TELEPORT_EASING)
+    } else {
+        Tween::slide(transform, grid2d.center(), MOVE_DURATION * cells as f32, MOVE_EASING)
+    };
+    tweens.insert(entity, tween).expect("moved entity is alive");
+}
+
+/// Move the player by the arrow keys following `rules::plan_move`,
+/// and record the turn in `MoveHistory`.
 #[derive(Default, SystemDesc)]
 pub struct PlayerMoveSystem {
     move_timer: u32
@@ -20,22 +92,40 @@ pub struct PlayerMoveSystem {
 
 impl<'s> System<'s> for PlayerMoveSystem {
     type SystemData = (
+        Entities<'s>,
         ReadStorage<'s, Player>,
         ReadStorage<'s, Obstacle>,
+        ReadStorage<'s, Pushable>,
+        ReadStorage<'s, Key>,
+        ReadStorage<'s, Ice>,
+        ReadStorage<'s, Teleporter>,
+        ReadStorage<'s, OneWay>,
+        WriteStorage<'s, Inventory>,
+        WriteStorage<'s, Hidden>,
         WriteStorage<'s, Transform>,
         WriteStorage<'s, Grid2D>,
+        WriteStorage<'s, Tween>,
         Read<'s, InputHandler<StringBindings>>,
+        Read<'s, CurrentStageData>,
+        Write<'s, MoveHistory>,
+        Write<'s, StageStats>,
+        Read<'s, SaveData>,
+        Option<Write<'s, Occupancy>>,
     );
 
-    fn run(&mut self, (player, obstacles, mut transforms, mut grid2ds, input): Self::SystemData) {
-        let movement = if input.key_is_down(VirtualKeyCode::Up) {
-            Grid2DDelta::new(0, 1)
-        } else if input.key_is_down(VirtualKeyCode::Down) {
-            Grid2DDelta::new(0, -1)
-        } else if input.key_is_down(VirtualKeyCode::Left) {
-            Grid2DDelta::new(-1, 0)
-        } else if input.key_is_down(VirtualKeyCode::Right) {
-            Grid2DDelta::new(1, 0)
+    fn run(&mut self, (entities, player, obstacles, pushables, keys, ices, teleporters, one_ways, mut inventories, mut hiddens, mut transforms, mut grid2ds, mut tweens, input, stage_data, mut history, mut stats, save_data, occupancy): Self::SystemData) {
+        if stage_data.state != GameState::Play {
+            return;
+        }
+        let pressed = |action| input.action_is_down(action).unwrap_or(false);
+        let direction = if pressed(actions::UP) {
+            Direction::Up
+        } else if pressed(actions::DOWN) {
+            Direction::Down
+        } else if pressed(actions::LEFT) {
+            Direction::Left
+        } else if pressed(actions::RIGHT) {
+            Direction::Right
         } else {
             self.move_timer = 0;
             return
@@ -44,19 +134,73 @@ impl<'s> System<'s> for PlayerMoveSystem {
             self.move_timer -= 1;
             return;
         }
-        let obstacles_place = (&obstacles, &grid2ds).join()
-            .map(|(_, grid)| grid.clone())
-            .collect::<Vec<Grid2D>>();
-
-        let (_, transform, grid2d) = (&player, &mut transforms, &mut grid2ds).join().next().unwrap();
-        let next_grid = grid2d.clone() + movement;
-        if !obstacles_place.contains(&next_grid) {
-            *grid2d = next_grid;
-            transform.set_translation(
-                *Transform::from(grid2d.clone()).translation()
-            );
+        let mut occupancy = match occupancy {
+            Some(occupancy) => occupancy,
+            None => return
+        };
+        let (entity, _, grid2d) = match (&entities, &player, &grid2ds).join().next() {
+            Some(v) => v,
+            None => return
+        };
+        let position = grid2d.clone();
+        occupancy.sync(&entities, &grid2ds);
+        let board = OccupiedBoard {
+            occupancy: &occupancy,
+            obstacles: &obstacles,
+            pushables: &pushables,
+            keys: &keys,
+            ices: &ices,
+            teleporters: &teleporters,
+            one_ways: &one_ways,
+        };
+
+        let animations = save_data.settings.animations;
+        if let Some(planned) = plan_move(&board, &position, direction) {
+            let pushed = planned.pushed.as_ref().and_then(|(from, to)| {
+                let (pushed, _) = occupancy.find(from, &pushables)?;
+                Some((pushed, to.clone() - from.clone()))
+            });
+            let picked_up = planned.path.iter()
+                .filter_map(|at| occupancy.find(at, &keys).map(|(key, letter)| (key, letter.0, at.clone())))
+                .collect::<Vec<_>>();
+
+            let mut turn = Vec::new();
+            let delta = planned.end().clone() - position;
+            let moves = std::iter::once((entity, delta, (planned.path.len(), planned.teleport.is_some())))
+                .chain(pushed.map(|(pushed, delta)| (pushed, delta, (1, false))));
+            for (moved, delta, animation) in moves {
+                if let Some(grid2d) = grid2ds.get_mut(moved) {
+                    *grid2d += delta.clone();
+                    move_sprite(moved, grid2d, animation, &mut transforms, &mut tweens, animations);
+                }
+                turn.push(Change::Moved { entity: moved, delta });
+            }
+            stats.moves += 1;
+
+            // a collected key leaves the grid, so it can't be picked up twice
+            for (key, letter, at) in picked_up {
+                if let Some(inventory) = inventories.get_mut(entity) {
+                    inventory.add(letter);
+                }
+                grid2ds.remove(key);
+                hiddens.insert(key, Hidden).expect("key entity is alive");
+                turn.push(Change::Collected { player: entity, key, letter, at });
+            }
+            history.record(turn);
+        } else {
+            stats.bumps += 1;
+            if animations {
+                let (x, y) = position.center();
+                let (dy, dx) = direction.offset();
+                let towards = (
+                    x + dx as f32 * BUMP_DISTANCE * CELL_SIZE,
+                    y + dy as f32 * BUMP_DISTANCE * CELL_SIZE,
+                );
+                let tween = Tween::bump((x, y), towards, BUMP_DURATION, BUMP_EASING);
+                tweens.insert(entity, tween).expect("player entity is alive");
+            }
         }
 
         self.move_timer += FREEZE_TIME;
     }
-}
\ No newline at end of file
+}
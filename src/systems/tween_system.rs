@@ -0,0 +1,39 @@
+use amethyst::{
+    core::{math::Vector3, Time, Transform},
+    derive::SystemDesc,
+    ecs::{Entities, Join, Read, System, SystemData, WriteStorage},
+};
+
+use crate::components::tween::Tween;
+
+/// Advance every `Tween` and drop the finished ones.
+#[derive(Default, SystemDesc)]
+pub struct TweenSystem;
+
+impl<'s> System<'s> for TweenSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Tween>,
+        WriteStorage<'s, Transform>,
+        Read<'s, Time>,
+    );
+
+    fn run(&mut self, (entities, mut tweens, mut transforms, time): Self::SystemData) {
+        let mut finished = Vec::new();
+        for (entity, tween, transform) in (&entities, &mut tweens, &mut transforms).join() {
+            tween.elapsed += time.delta_seconds();
+            let (x, y) = tween.position();
+            transform.set_translation_x(x);
+            transform.set_translation_y(y);
+            let scale = tween.scale();
+            transform.set_scale(Vector3::new(scale, scale, 1.));
+            if tween.is_finished() {
+                finished.push(entity);
+            }
+        }
+
+        for entity in finished {
+            tweens.remove(entity);
+        }
+    }
+}
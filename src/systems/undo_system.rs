@@ -0,0 +1,98 @@
+use amethyst::{
+    core::{math::Vector3, Hidden, Transform},
+    derive::SystemDesc,
+    ecs::{Read, System, SystemData, Write, WriteStorage},
+    input::{InputHandler, StringBindings},
+};
+
+use crate::{
+    components::{enemy::Enemy, grid2d::Grid2D, inventory::Inventory, tween::Tween},
+    config::{actions, FREEZE_TIME},
+    resources::{Change, CurrentStageData, GameState, MoveHistory, StageStats},
+};
+
+/// Undo and redo the turns recorded in `MoveHistory`.
+#[derive(Default, SystemDesc)]
+pub struct UndoSystem {
+    undo_timer: u32
+}
+
+impl<'s> System<'s> for UndoSystem {
+    type SystemData = (
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, Grid2D>,
+        WriteStorage<'s, Tween>,
+        WriteStorage<'s, Inventory>,
+        WriteStorage<'s, Hidden>,
+        WriteStorage<'s, Enemy>,
+        Read<'s, InputHandler<StringBindings>>,
+        Read<'s, CurrentStageData>,
+        Write<'s, MoveHistory>,
+        Write<'s, StageStats>,
+    );
+
+    fn run(&mut self, (mut transforms, mut grid2ds, mut tweens, mut inventories, mut hiddens, mut enemies, input, stage_data, mut history, mut stats): Self::SystemData) {
+        if stage_data.state != GameState::Play {
+            return;
+        }
+        let pressed = |action| input.action_is_down(action).unwrap_or(false);
+        let undo = if pressed(actions::UNDO) {
+            true
+        } else if pressed(actions::REDO) {
+            false
+        } else {
+            self.undo_timer = 0;
+            return
+        };
+        if self.undo_timer > 0 {
+            self.undo_timer -= 1;
+            return;
+        }
+
+        let turn = if undo { history.undo() } else { history.redo() };
+        if turn.is_some() {
+            stats.moves = if undo { stats.moves.saturating_sub(1) } else { stats.moves + 1 };
+        }
+        for change in turn.unwrap_or_default() {
+            match change {
+                Change::Moved { entity, delta } => {
+                    if let Some(grid2d) = grid2ds.get_mut(entity) {
+                        *grid2d += delta;
+                        if let Some(transform) = transforms.get_mut(entity) {
+                            grid2d.update_transform(transform);
+                            // in case a teleport was cut short
+                            transform.set_scale(Vector3::new(1., 1., 1.));
+                        }
+                        tweens.remove(entity);
+                    }
+                }
+                Change::Collected { player, key, letter, .. } => {
+                    if let Some(inventory) = inventories.get_mut(player) {
+                        inventory.add(letter);
+                    }
+                    grid2ds.remove(key);
+                    hiddens.insert(key, Hidden).expect("key entity is alive");
+                }
+                Change::Dropped { player, key, letter, at } => {
+                    if let Some(inventory) = inventories.get_mut(player) {
+                        inventory.remove(letter);
+                    }
+                    grid2ds.insert(key, at).expect("key entity is alive");
+                    hiddens.remove(key);
+                }
+                Change::Patrolled { entity, to, .. } => {
+                    if let (Some(enemy), Some(grid2d)) = (enemies.get_mut(entity), grid2ds.get_mut(entity)) {
+                        enemy.step = to;
+                        *grid2d = enemy.position();
+                        if let Some(transform) = transforms.get_mut(entity) {
+                            grid2d.update_transform(transform);
+                        }
+                        tweens.remove(entity);
+                    }
+                }
+            }
+        }
+
+        self.undo_timer += FREEZE_TIME;
+    }
+}
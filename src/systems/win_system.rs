@@ -1,41 +1,82 @@
 use amethyst::{
     derive::SystemDesc,
-    ecs::{Join, ReadStorage, System, SystemData, WriteExpect},
+    ecs::{Entities, Join, Read, ReadStorage, System, SystemData, Write, WriteExpect},
 };
 
 use crate::{
     components::{
-        basics::{Player, Goal},
-        grid2d::{Grid2D},
+        basics::{Player, Pushable},
+        grid2d::{Direction, Grid2D},
     },
-    resources::{CurrentStageData, GameState},
+    resources::{CurrentStageData, GameState, Occupancy, StageTargets},
+    rules::{is_cleared, Board},
 };
 
+/// `Board` of the boxes of `Occupancy`, all `is_cleared` asks about
+struct BoxBoard<'a> {
+    occupancy: &'a Occupancy,
+    pushables: &'a ReadStorage<'a, Pushable>,
+}
+
+impl Board<Grid2D> for BoxBoard<'_> {
+    fn is_blocked(&self, cell: &Grid2D) -> bool {
+        !self.occupancy.contains(cell)
+    }
+
+    fn has_box(&self, cell: &Grid2D) -> bool {
+        self.occupancy.find(cell, self.pushables).is_some()
+    }
+
+    fn has_key(&self, _: &Grid2D) -> bool {
+        false
+    }
+
+    fn is_ice(&self, _: &Grid2D) -> bool {
+        false
+    }
+
+    fn allows(&self, _: &Grid2D, _: Direction) -> bool {
+        true
+    }
+
+    fn teleporter_partner(&self, _: &Grid2D) -> Option<Grid2D> {
+        None
+    }
+}
+
+/// Win the stage once `rules::is_cleared`: the player stands on a goal with a box on every pad.
 #[derive(Default, SystemDesc)]
 pub struct WinSystem;
 
 impl<'s> System<'s> for WinSystem {
     type SystemData = (
+        Entities<'s>,
         ReadStorage<'s, Player>,
-        ReadStorage<'s, Goal>,
+        ReadStorage<'s, Pushable>,
         ReadStorage<'s, Grid2D>,
+        Read<'s, StageTargets>,
+        Option<Write<'s, Occupancy>>,
         WriteExpect<'s, CurrentStageData>
     );
 
-    fn run(&mut self, (player, goal, grid2ds, mut stage_data): Self::SystemData) {
-
-        let (_, player_position) = match (&player, &grid2ds).join().next() {
-            Some(v) => v,
-            None => return 
+    fn run(&mut self, (entities, player, pushables, grid2ds, targets, occupancy, mut stage_data): Self::SystemData) {
+        if stage_data.state != GameState::Play {
+            return;
+        }
+        let mut occupancy = match occupancy {
+            Some(occupancy) => occupancy,
+            None => return
         };
+        occupancy.sync(&entities, &grid2ds);
 
-        let (_, goal_position) = match (&goal, &grid2ds).join().next() {
-            Some(v) => v,
-            None => return 
+        let player_position = match (&player, &grid2ds).join().next() {
+            Some((_, grid)) => grid,
+            None => return
         };
+        let board = BoxBoard { occupancy: &occupancy, pushables: &pushables };
 
-        if player_position == goal_position {
+        if is_cleared(&board, player_position, &targets.goals, &targets.pads) {
             stage_data.state = GameState::Win;
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,10 @@
+//! Fixtures shared by the unit tests.
+use std::path::PathBuf;
+
+/// an empty directory named after `name` and the test process, to write test files in
+pub fn temporary_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("maze_game_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,149 @@
+//! A world and dispatcher running the systems of the game without a window,
+//! fed with key presses frame by frame.
+use std::sync::Arc;
+
+use amethyst::{
+    assets::{AssetStorage, Loader},
+    config::Config,
+    core::{Parent, Transform},
+    ecs::{rayon::ThreadPoolBuilder, Dispatcher, DispatcherBuilder, Join, World, WorldExt},
+    input::{Bindings, InputEvent, InputHandler, StringBindings},
+    renderer::{resources::Tint, Camera, SpriteRender, SpriteSheet, Texture},
+    shrev::EventChannel,
+    winit::{DeviceId, ElementState, Event, KeyboardInput, ModifiersState, WindowEvent, WindowId},
+};
+
+use maze_game::{
+    components::{
+        basics::{Goal, Pad, Player, Wall},
+        grid2d::Grid2D,
+    },
+    resources::{CurrentStageData, GameState, MoveHistory, SaveData, StageStats},
+    stage::Stage,
+    states::spawn_stage,
+    systems::{CameraSystem, DoorSystem, EnemySystem, PlayerMoveSystem, UndoSystem, WinSystem},
+};
+
+pub use amethyst::winit::VirtualKeyCode as Key;
+
+pub struct Harness {
+    pub world: World,
+    dispatcher: Dispatcher<'static, 'static>,
+}
+
+impl Harness {
+    /// `stage` written like the stage files
+    pub fn new(stage: &str) -> Self {
+        Self::with_stage(&stage.parse().expect("the stage parses"))
+    }
+
+    /// the systems wired like in `main`
+    pub fn with_stage(stage: &Stage) -> Self {
+        let resources = concat!(env!("CARGO_MANIFEST_DIR"), "/resources");
+
+        let mut world = World::new();
+        let mut dispatcher = DispatcherBuilder::new()
+            .with(PlayerMoveSystem::default(), "player_move_system", &[])
+            .with(EnemySystem, "enemy_system", &["player_move_system"])
+            .with(UndoSystem::default(), "undo_system", &["player_move_system", "enemy_system"])
+            .with(DoorSystem, "door_system", &["player_move_system", "undo_system"])
+            .with(WinSystem, "win_system", &["player_move_system", "enemy_system", "undo_system"])
+            .with(CameraSystem, "camera_system", &["player_move_system", "enemy_system", "undo_system"])
+            .build();
+        dispatcher.setup(&mut world);
+
+        // components only the renderer and the stage spawning touch
+        world.register::<Camera>();
+        world.register::<Parent>();
+        world.register::<SpriteRender>();
+        world.register::<Tint>();
+        world.register::<Wall>();
+        world.register::<Goal>();
+        world.register::<Pad>();
+
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        world.insert(Loader::new(resources, Arc::new(pool)));
+        world.insert(AssetStorage::<Texture>::new());
+        world.insert(AssetStorage::<SpriteSheet>::new());
+
+        let mut input = InputHandler::<StringBindings>::new();
+        input.bindings = Bindings::load(format!("{}/input.ron", resources)).expect("the bindings load");
+        world.insert(input);
+        world.insert(EventChannel::<InputEvent<StringBindings>>::new());
+
+        let mut save_data = SaveData::default();
+        save_data.settings.animations = false;
+        world.insert(save_data);
+        world.insert(CurrentStageData::default());
+        world.insert(MoveHistory::default());
+        world.insert(StageStats::default());
+
+        spawn_stage(&mut world, stage);
+        Self { world, dispatcher }
+    }
+
+    fn send_key(&mut self, key: Key, state: ElementState) {
+        let event = Event::WindowEvent {
+            // the input handler never looks at where the event comes from
+            window_id: unsafe { WindowId::dummy() },
+            event: WindowEvent::KeyboardInput {
+                device_id: unsafe { DeviceId::dummy() },
+                input: KeyboardInput {
+                    scancode: 0,
+                    state,
+                    virtual_keycode: Some(key),
+                    modifiers: ModifiersState::default(),
+                },
+            },
+        };
+        let mut events = self.world.write_resource::<EventChannel<InputEvent<StringBindings>>>();
+        self.world.write_resource::<InputHandler<StringBindings>>().send_event(&event, &mut events, 1.);
+    }
+
+    /// hold `key` down from the next frame on
+    pub fn press(&mut self, key: Key) {
+        self.send_key(key, ElementState::Pressed);
+    }
+
+    pub fn release(&mut self, key: Key) {
+        self.send_key(key, ElementState::Released);
+    }
+
+    /// run the systems for `frames` frames
+    pub fn step(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.dispatcher.dispatch(&self.world);
+            self.world.maintain();
+        }
+    }
+
+    /// press `key` for a single frame
+    pub fn tap(&mut self, key: Key) {
+        self.press(key);
+        self.step(1);
+        self.release(key);
+    }
+
+    pub fn player(&self) -> Grid2D {
+        let players = self.world.read_storage::<Player>();
+        let grid2ds = self.world.read_storage::<Grid2D>();
+        let (_, grid2d) = (&players, &grid2ds).join().next().expect("the stage has a player");
+        grid2d.clone()
+    }
+
+    /// `(x, y)` of the camera in world coordinates
+    pub fn camera(&self) -> (f32, f32) {
+        let cameras = self.world.read_storage::<Camera>();
+        let transforms = self.world.read_storage::<Transform>();
+        let (_, transform) = (&cameras, &transforms).join().next().expect("the stage has a camera");
+        (transform.translation().x, transform.translation().y)
+    }
+
+    pub fn state(&self) -> GameState {
+        self.world.read_resource::<CurrentStageData>().state
+    }
+
+    pub fn stats(&self) -> StageStats {
+        (*self.world.read_resource::<StageStats>()).clone()
+    }
+}
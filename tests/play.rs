@@ -0,0 +1,152 @@
+//! The stage rules as the systems play them, frame by frame.
+mod harness;
+
+use amethyst::ecs::{Join, WorldExt};
+use harness::{Harness, Key};
+use maze_game::{
+    components::grid2d::{Direction, Grid2D},
+    config::{CELL_SIZE, FREEZE_TIME},
+    generator::{generate, GeneratorConfig},
+    resources::GameState,
+    stage::solve,
+};
+
+#[test]
+fn holding_a_key_moves_once_per_freeze_time() {
+    let mut game = Harness::new("@.....G");
+    game.press(Key::Right);
+    game.step(1);
+    assert_eq!(game.player(), Grid2D::new(1, 0));
+    game.step(FREEZE_TIME);
+    assert_eq!(game.player(), Grid2D::new(1, 0));
+    game.step(1);
+    assert_eq!(game.player(), Grid2D::new(2, 0));
+
+    // releasing the key lifts the freeze at once
+    game.release(Key::Right);
+    game.step(1);
+    game.tap(Key::Right);
+    assert_eq!(game.player(), Grid2D::new(3, 0));
+    assert_eq!(game.stats().moves, 3);
+}
+
+#[test]
+fn walls_and_the_border_stop_the_player() {
+    let mut game = Harness::new("@#.\n..G");
+    // nothing stands around the stage, the outside blocks the player by itself
+    let cells = game.world.read_storage::<Grid2D>().join().cloned().collect::<Vec<_>>();
+    assert!(cells.iter().all(|cell| (0..3).contains(&cell.x()) && (0..2).contains(&cell.y())));
+    game.tap(Key::Right);
+    game.step(1);
+    game.tap(Key::Left);
+    game.step(1);
+    game.tap(Key::Down);
+    assert_eq!(game.player(), Grid2D::new(0, 0));
+    assert_eq!(game.stats().moves, 0);
+    assert_eq!(game.stats().bumps, 3);
+
+    game.step(1);
+    game.tap(Key::Up);
+    assert_eq!(game.player(), Grid2D::new(0, 1));
+}
+
+#[test]
+fn reaching_the_goal_wins_and_stops_the_moves() {
+    let mut game = Harness::new("@.G.");
+    game.tap(Key::Right);
+    assert_eq!(game.state(), GameState::Play);
+    game.step(1);
+    game.tap(Key::Right);
+    assert_eq!(game.state(), GameState::Win);
+
+    game.step(1);
+    game.tap(Key::Right);
+    assert_eq!(game.player(), Grid2D::new(2, 0));
+}
+
+#[test]
+fn boxes_must_cover_the_pads_before_the_goal_counts() {
+    let mut game = Harness::new("G@.$+");
+    game.tap(Key::Left);
+    assert_eq!(game.state(), GameState::Play);
+    game.step(1);
+    for _ in 0..3 {
+        game.tap(Key::Right);
+        game.step(1);
+    }
+    assert_eq!(game.player(), Grid2D::new(3, 0));
+    for _ in 0..3 {
+        game.tap(Key::Left);
+        game.step(1);
+    }
+    assert_eq!(game.state(), GameState::Win);
+}
+
+#[test]
+fn undo_puts_keys_and_boxes_back_where_they_block() {
+    let mut game = Harness::new("a@A$.G");
+    for key in [Key::Left, Key::Right, Key::Right, Key::Right] {
+        game.tap(key);
+        game.step(1);
+    }
+    assert_eq!(game.player(), Grid2D::new(3, 0));
+
+    for _ in 0..4 {
+        game.tap(Key::Z);
+        game.step(1);
+    }
+    assert_eq!(game.player(), Grid2D::new(1, 0));
+    // the key went back on the floor, so the door closed again
+    game.tap(Key::Right);
+    game.step(1);
+    assert_eq!(game.player(), Grid2D::new(1, 0));
+    assert_eq!(game.stats().bumps, 1);
+
+    // and the box went back next to it
+    for key in [Key::Left, Key::Right, Key::Right, Key::Right, Key::Right] {
+        game.tap(key);
+        game.step(1);
+    }
+    assert_eq!(game.player(), Grid2D::new(4, 0));
+    assert_eq!(game.stats().bumps, 1);
+}
+
+#[test]
+fn a_long_thin_stage_scrolls_along_its_length_only() {
+    let stage = std::iter::once(format!("@{}G", ".".repeat(38)))
+        .chain(std::iter::repeat_n(".".repeat(40), 9))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut game = Harness::new(&stage);
+    game.step(1);
+    // 32 cells across, 2 of them beyond the left edge
+    assert_eq!(game.camera(), (14. * CELL_SIZE, 5. * CELL_SIZE));
+
+    for _ in 0..20 {
+        game.tap(Key::Right);
+        game.step(1);
+    }
+    game.step(1);
+    assert_eq!(game.camera(), (20.5 * CELL_SIZE, 5. * CELL_SIZE));
+}
+
+#[test]
+fn plays_through_a_500_by_500_maze() {
+    let config = GeneratorConfig { width: 250, height: 250, seed: 7, ..GeneratorConfig::default() };
+    let stage = generate(&config);
+    assert_eq!(stage.size(), (499, 499));
+    let solution = solve(&stage).unwrap();
+
+    let mut game = Harness::with_stage(&stage);
+    for direction in solution {
+        let key = match direction {
+            Direction::Up => Key::Up,
+            Direction::Down => Key::Down,
+            Direction::Left => Key::Left,
+            Direction::Right => Key::Right,
+        };
+        game.tap(key);
+        game.step(1);
+    }
+    assert_eq!(game.state(), GameState::Win);
+}